@@ -0,0 +1,287 @@
+use crate::event::Event;
+use crate::http::{self, Request};
+use crate::job::Job;
+use crate::satnogs::Data;
+use crate::state::State;
+use crate::station::Station;
+use crate::status::{JobInfo, Status};
+use crate::sysinfo::SysInfo;
+use crate::ui::LogQueue;
+use crate::Result;
+
+use anyhow::Context;
+use log::{debug, warn};
+use satnogs_network_client as snc;
+use serde_derive::Serialize;
+use serde_json::json;
+
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Seconds between two keep alive comments on the event stream
+const KEEP_ALIVE_INTERVAL: u64 = 15;
+
+/// Seconds a request waits for the ui to answer
+const REQUEST_TIMEOUT: u64 = 5;
+
+/// A station with all its jobs
+#[derive(Serialize)]
+struct StationView<'a> {
+    info: &'a snc::StationInfo,
+    sys_info: &'a SysInfo,
+    jobs: Vec<JobView<'a>>,
+}
+
+impl<'a> StationView<'a> {
+    fn new(station: &'a Station) -> Self {
+        StationView {
+            info: &station.info,
+            sys_info: &station.sys_info,
+            jobs: station.jobs.iter().map(JobView::new).collect(),
+        }
+    }
+}
+
+/// A job with the observation details and the tracks of the satellite
+#[derive(Serialize)]
+struct JobView<'a> {
+    #[serde(flatten)]
+    job: JobInfo<'a>,
+    observation: &'a snc::Observation,
+    /// Latitude and longitude of the footprint of the satellite
+    footprint: &'a [(f64, f64)],
+    /// Latitude and longitude of the satellite for the next orbits
+    ground_track: &'a [(f64, f64)],
+    /// Azimuth and elevation of the pass
+    polar_track: &'a [(f64, f64)],
+}
+
+impl<'a> JobView<'a> {
+    fn new(job: &'a Job) -> Self {
+        JobView {
+            job: JobInfo::new(job),
+            observation: &job.observation,
+            footprint: &job.vessel.footprint,
+            ground_track: &job.vessel.ground_track,
+            polar_track: &job.vessel.polar_track,
+        }
+    }
+}
+
+/// Answers a GET request for `path` with the JSON representation of the state.
+/// Returns `None` for unknown paths.
+pub fn answer(path: &str, state: &State, logs: &LogQueue) -> Option<String> {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    let json = match segments.as_slice() {
+        ["api", "status"] => serde_json::to_string(&Status::new(state)),
+        ["api", "stations"] => serde_json::to_string(
+            &state
+                .stations
+                .values()
+                .map(StationView::new)
+                .collect::<Vec<_>>(),
+        ),
+        ["api", "stations", id] => {
+            let station = id.parse().ok().and_then(|id| state.stations.get(&id))?;
+            serde_json::to_string(&StationView::new(station))
+        }
+        ["api", "rotator"] => serde_json::to_string(&Status::new(state).rotator),
+        ["api", "logs"] => serde_json::to_string(
+            &logs
+                .iter()
+                .map(|(time, level, message)| {
+                    json!({ "time": time, "level": level.to_string(), "message": message })
+                })
+                .collect::<Vec<_>>(),
+        ),
+        ["api", "signals"] => serde_json::to_string(&state.signal_history.iter().collect::<Vec<_>>()),
+        _ => return None,
+    };
+
+    match json {
+        Ok(json) => Some(json),
+        Err(err) => {
+            warn!("Failed to serialize {}: {}", path, err);
+            None
+        }
+    }
+}
+
+/// Serves the state as JSON and a stream of state changes as server-sent events.
+/// Requests are answered by the ui which owns the state.
+pub struct Api {
+    events_tx: SyncSender<String>,
+}
+
+impl Api {
+    pub fn serve(address: &str, ui_tx: SyncSender<Event>) -> Result<Self> {
+        let listener = TcpListener::bind(address)
+            .with_context(|| format!("couldn't listen for API requests on {}", address))?;
+        let subscribers: Arc<Mutex<Vec<TcpStream>>> = Default::default();
+        let (events_tx, events_rx) = sync_channel(100);
+
+        let shared = subscribers.clone();
+        thread::spawn(move || broadcast(events_rx, &shared));
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        debug!("Failed to accept API connection: {}", err);
+                        continue;
+                    }
+                };
+
+                let ui_tx = ui_tx.clone();
+                let subscribers = subscribers.clone();
+                thread::spawn(move || {
+                    if let Err(err) = handle(stream, &ui_tx, &subscribers) {
+                        debug!("Failed to answer API request: {}", err);
+                    }
+                });
+            }
+            warn!("HTTP API stopped");
+        });
+
+        Ok(Api { events_tx })
+    }
+
+    /// Sends a state change to all clients of the event stream
+    pub fn send_event<T: serde::Serialize>(&self, kind: &str, payload: &T) {
+        match serde_json::to_string(payload) {
+            Ok(data) => {
+                // never block the ui, slow clients miss events
+                let _ = self
+                    .events_tx
+                    .try_send(format!("event: {}\ndata: {}\n\n", kind, data));
+            }
+            Err(err) => warn!("Failed to serialize {} event: {}", kind, err),
+        }
+    }
+
+    /// Forwards the events changing the state to the event stream
+    pub fn handle_event(&self, event: &Event) {
+        match event {
+            Event::CommandResponse(Data::Jobs(station, jobs)) => {
+                self.send_event("jobs", &json!({ "station": station, "jobs": jobs.len() }))
+            }
+            Event::CommandResponse(Data::StationInfo(info)) => self.send_event("station", info),
+            Event::Log((level, message)) => self.send_event(
+                "log",
+                &json!({ "level": level.to_string(), "message": message }),
+            ),
+            Event::RigStatus(status) => self.send_event("rig", status),
+            Event::RotatorPosition(azimuth, elevation) => self.send_event(
+                "rotator",
+                &json!({ "azimuth": azimuth, "elevation": elevation }),
+            ),
+            Event::SystemInfo(stations, sys_info) => self.send_event(
+                "sysinfo",
+                &json!({ "stations": stations, "sys_info": sys_info }),
+            ),
+            Event::WaterfallCreated(observation, station, center_freq, _) => self.send_event(
+                "waterfall",
+                &json!({
+                    "event": "created",
+                    "observation": observation,
+                    "station": station,
+                    "center_frequency": center_freq,
+                }),
+            ),
+            Event::WaterfallClosed(observation) => self.send_event(
+                "waterfall",
+                &json!({ "event": "closed", "observation": observation }),
+            ),
+            _ => {}
+        }
+    }
+}
+
+fn handle(
+    stream: TcpStream,
+    ui_tx: &SyncSender<Event>,
+    subscribers: &Mutex<Vec<TcpStream>>,
+) -> Result<()> {
+    let request = Request::read(&stream)?;
+    if request.method != "GET" {
+        return http::respond(
+            &stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n",
+        );
+    }
+
+    if request.path == "/api/events" {
+        write!(
+            &stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n"
+        )?;
+        stream.set_write_timeout(Some(Duration::new(REQUEST_TIMEOUT, 0)))?;
+        subscribers.lock().unwrap().push(stream);
+        return Ok(());
+    }
+
+    let (reply_tx, reply_rx) = sync_channel(1);
+    ui_tx.send(Event::ApiRequest(request.path, reply_tx))?;
+
+    match reply_rx.recv_timeout(Duration::new(REQUEST_TIMEOUT, 0)) {
+        Ok(Some(json)) => http::respond(&stream, "200 OK", "application/json", &json),
+        Ok(None) => http::respond(&stream, "404 Not Found", "text/plain", "not found\n"),
+        Err(_) => http::respond(
+            &stream,
+            "503 Service Unavailable",
+            "text/plain",
+            "no answer from the monitor\n",
+        ),
+    }
+}
+
+/// Writes the events to all clients of the event stream and drops the clients
+/// which went away
+fn broadcast(events: Receiver<String>, subscribers: &Mutex<Vec<TcpStream>>) {
+    loop {
+        let message = match events.recv_timeout(Duration::new(KEEP_ALIVE_INTERVAL, 0)) {
+            Ok(message) => message,
+            Err(RecvTimeoutError::Timeout) => String::from(": keep-alive\n\n"),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+
+        // write outside of the lock, a slow client must not block new subscribers
+        let mut streams = std::mem::take(&mut *subscribers.lock().unwrap());
+        streams.retain(|mut stream| stream.write_all(message.as_bytes()).is_ok());
+
+        let mut subscribers = subscribers.lock().unwrap();
+        streams.append(&mut subscribers);
+        *subscribers = streams;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use circular_queue::CircularQueue;
+
+    #[test]
+    fn answers_known_paths() {
+        let state = State::new();
+        let mut logs = CircularQueue::with_capacity(10);
+        logs.push((Utc::now(), log::Level::Warn, String::from("rotator lost")));
+
+        assert_eq!(answer("/api/stations", &state, &logs).unwrap(), "[]");
+        assert_eq!(answer("/api/rotator/", &state, &logs).unwrap(), "null");
+        assert!(answer("/api/logs", &state, &logs)
+            .unwrap()
+            .contains(r#""level":"WARN","message":"rotator lost""#));
+        assert!(answer("/api/stations/175", &state, &logs).is_none());
+        assert!(answer("/api/stations/x", &state, &logs).is_none());
+        assert!(answer("/metrics", &state, &logs).is_none());
+    }
+}
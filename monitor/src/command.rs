@@ -0,0 +1,137 @@
+use crate::job::Job;
+use crate::Result;
+
+use log::{info, warn};
+
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Environment variables describing a job of a station
+pub fn job_environment(station: u64, job: &Job) -> Vec<(String, String)> {
+    vec![
+        (String::from("SATNOGS_STATION_ID"), station.to_string()),
+        (String::from("SATNOGS_JOB_ID"), job.id().to_string()),
+        (
+            String::from("SATNOGS_NORAD_ID"),
+            job.observation.norad_cat_id.to_string(),
+        ),
+        (
+            String::from("SATNOGS_SATELLITE"),
+            job.vessel_name().to_string(),
+        ),
+        (String::from("SATNOGS_START"), job.start().to_rfc3339()),
+        (String::from("SATNOGS_END"), job.end().to_rfc3339()),
+        (
+            String::from("SATNOGS_FREQUENCY"),
+            format!("{:.0}", job.frequency_mhz() * 1_000_000.0),
+        ),
+        (String::from("SATNOGS_MODE"), job.mode().to_string()),
+    ]
+}
+
+/// Runs `command` with `sh -c` and logs its output prefixed with `name`. The
+/// command is killed if it runs longer than `timeout`.
+pub fn run(name: &str, command: &str, environment: &[(String, String)], timeout: Duration) {
+    match execute(command, environment, timeout) {
+        Ok((status, output)) => {
+            for line in output.lines().filter(|line| !line.trim().is_empty()) {
+                info!("{}: {}", name, line);
+            }
+            match status {
+                Some(status) if status.success() => {}
+                Some(status) => warn!("{} failed with {}", name, status),
+                None => warn!("{} killed after {} seconds", name, timeout.as_secs()),
+            }
+        }
+        Err(err) => warn!("Failed to run {}: {}", name, err),
+    }
+}
+
+/// Returns the exit status, `None` if the command timed out, and the output
+fn execute(
+    command: &str,
+    environment: &[(String, String)],
+    timeout: Duration,
+) -> Result<(Option<std::process::ExitStatus>, String)> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(environment.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // a process group of its own, so the children are killed with it
+        .process_group(0)
+        .spawn()?;
+
+    // the pipes are drained while waiting, a full pipe would block the command
+    let stdout = collect(child.stdout.take());
+    let stderr = collect(child.stderr.take());
+
+    let status = wait(&mut child, timeout)?;
+
+    let mut output = stdout.join().unwrap_or_default();
+    output.push_str(&stderr.join().unwrap_or_default());
+
+    Ok((status, output))
+}
+
+fn collect<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        String::from_utf8_lossy(&output).into_owned()
+    })
+}
+
+fn wait(child: &mut Child, timeout: Duration) -> Result<Option<std::process::ExitStatus>> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if started.elapsed() >= timeout {
+            // children of the shell would keep the pipes open
+            let _ = Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", child.id())])
+                .status();
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_output_and_environment() {
+        let environment = [(String::from("SATNOGS_JOB_ID"), String::from("1234"))];
+        let (status, output) = execute(
+            "echo job $SATNOGS_JOB_ID; echo oops >&2; exit 3",
+            &environment,
+            Duration::new(5, 0),
+        )
+        .unwrap();
+
+        assert_eq!(status.and_then(|status| status.code()), Some(3));
+        assert_eq!(output, "job 1234\noops\n");
+    }
+
+    #[test]
+    fn kills_commands_running_too_long() {
+        let started = Instant::now();
+        let (status, _) = execute("sleep 10", &[], Duration::from_millis(200)).unwrap();
+
+        assert!(status.is_none());
+        assert!(started.elapsed() < Duration::new(5, 0));
+    }
+}
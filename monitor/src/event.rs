@@ -1,16 +1,25 @@
+use crate::recordings::Recording;
+use crate::rigctld_client::RigStatus;
 use crate::satnogs::Data;
 use crate::sysinfo::SysInfo;
 use log::Level;
+use std::sync::mpsc::SyncSender;
 
 pub enum Event {
+    /// Request of the http API for the JSON at a path, answered with `None` if
+    /// there is nothing at the path
+    ApiRequest(String, SyncSender<Option<String>>),
     Input(termion::event::Event),
     Log((Level, String)),
     CommandResponse(Data),
+    Recordings(Vec<Recording>),
     Resize,
+    RigStatus(RigStatus),
     RotatorPosition(f64, f64),
     SystemInfo(Vec<u64>, SysInfo),
     Tick,
-    WaterfallCreated(u64, Vec<f32>),
-    WaterfallData(i64, Vec<f32>),
+    /// Observation, station if known, center frequency and FFT bin frequencies
+    WaterfallCreated(u64, Option<u64>, f32, Vec<f32>),
+    WaterfallData(u64, i64, Vec<f32>),
     WaterfallClosed(u64),
 }
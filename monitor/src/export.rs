@@ -0,0 +1,232 @@
+use anyhow::Context as _;
+use tui::style::Color;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use crate::waterfall::{find_waterfall_files, WaterfallRecording};
+use crate::widgets::{color_index, tick_label, tick_step, VIRIDIS};
+use crate::Result;
+
+/// Images wider than this get their FFT bins reduced by taking the maximum
+const MAX_WIDTH: usize = 2048;
+
+const MARGIN_TOP: u32 = 20;
+const MARGIN_BOTTOM: u32 = 24;
+const MARGIN_LEFT: u32 = 48;
+const MARGIN_RIGHT: u32 = 32;
+
+const BACKGROUND: [u8; 3] = [0x00, 0x00, 0x00];
+const FOREGROUND: [u8; 3] = [0xa0, 0xa0, 0xa0];
+
+/// Glyphs are 3 x 5 pixel and scaled by this factor
+const FONT_SCALE: u32 = 2;
+const GLYPH_WIDTH: u32 = 4 * FONT_SCALE;
+
+/// Renders waterfall rows to a PNG image with the oldest row at the top, frequency
+/// offsets in kHz at the bottom and the time since the first row on the left.
+pub fn waterfall_png(
+    path: &Path,
+    frequencies: &[f32],
+    rows: &[(i64, Vec<f32>)],
+    db_range: [f32; 2],
+) -> Result<()> {
+    if rows.is_empty() || frequencies.is_empty() {
+        anyhow::bail!("No waterfall data");
+    }
+
+    let bin_size = frequencies.len().div_ceil(MAX_WIDTH);
+    let columns = frequencies.len().div_ceil(bin_size);
+
+    let width = MARGIN_LEFT + columns as u32 + MARGIN_RIGHT;
+    let height = MARGIN_TOP + rows.len() as u32 + MARGIN_BOTTOM;
+    let mut image = Image::new(width, height);
+
+    for (y, (_timestamp, row)) in rows.iter().enumerate() {
+        for (x, bins) in row.chunks(bin_size).enumerate() {
+            let db = bins.iter().fold(db_range[0], |res, val| res.max(*val));
+            image.set_pixel(
+                MARGIN_LEFT + x as u32,
+                MARGIN_TOP + y as u32,
+                rgb(VIRIDIS[color_index(db, db_range)]),
+            );
+        }
+    }
+
+    draw_frequency_axis(&mut image, frequencies, columns as u32, rows.len() as u32);
+    draw_time_axis(&mut image, rows);
+
+    image.save(path)
+}
+
+/// Renders the waterfall files found in `paths`. The images are written next to
+/// the waterfall files or into `output` if set.
+pub fn export_files(paths: &[PathBuf], output: Option<&Path>, db_range: [f32; 2]) -> Result<()> {
+    for file in find_waterfall_files(paths)? {
+        let recording = WaterfallRecording::from_file(&file)?;
+
+        let directory = match output {
+            Some(output) => output,
+            None => file.parent().unwrap_or_else(|| Path::new(".")),
+        };
+        let png = directory.join(file.with_extension("png").file_name().unwrap_or_default());
+
+        waterfall_png(&png, &recording.frequencies, &recording.rows, db_range)
+            .with_context(|| format!("Failed to export {}", file.display()))?;
+        println!("{} -> {}", file.display(), png.display());
+    }
+
+    Ok(())
+}
+
+fn draw_frequency_axis(image: &mut Image, frequencies: &[f32], columns: u32, rows: u32) {
+    let first = *frequencies.first().unwrap() as f64 / 1000.0;
+    let last = *frequencies.last().unwrap() as f64 / 1000.0;
+    let y = MARGIN_TOP + rows;
+
+    for x in 0..columns {
+        image.set_pixel(MARGIN_LEFT + x, y, FOREGROUND);
+    }
+
+    let step = tick_step(last - first, 8.0);
+    for tick in ((first / step).ceil() as i64..=(last / step).floor() as i64)
+        .map(|index| index as f64 * step)
+    {
+        let x = MARGIN_LEFT + ((tick - first) / (last - first) * (columns - 1) as f64) as u32;
+        for dy in 1..4 {
+            image.set_pixel(x, y + dy, FOREGROUND);
+        }
+
+        let label = tick_label(tick, step);
+        let label_width = label.len() as u32 * GLYPH_WIDTH;
+        image.draw_text(x.saturating_sub(label_width / 2), y + 6, &label, FOREGROUND);
+    }
+
+    image.draw_text(MARGIN_LEFT + columns + 4, y + 6, "kHz", FOREGROUND);
+}
+
+fn draw_time_axis(image: &mut Image, rows: &[(i64, Vec<f32>)]) {
+    let start = rows[0].0;
+    let seconds = |timestamp: i64| (timestamp - start) as f64 / 1_000_000.0;
+    let x = MARGIN_LEFT - 1;
+
+    for y in 0..rows.len() as u32 {
+        image.set_pixel(x, MARGIN_TOP + y, FOREGROUND);
+    }
+
+    let duration = seconds(rows[rows.len() - 1].0);
+    if duration <= 0.0 {
+        return;
+    }
+
+    let step = tick_step(duration, 10.0);
+    for tick in (0..=(duration / step).floor() as i64).map(|index| index as f64 * step) {
+        if let Some(y) = rows.iter().position(|row| seconds(row.0) >= tick) {
+            let y = MARGIN_TOP + y as u32;
+            for dx in 1..4 {
+                image.set_pixel(x - dx, y, FOREGROUND);
+            }
+
+            let label = tick_label(tick, step);
+            let label_width = label.len() as u32 * GLYPH_WIDTH;
+            image.draw_text(
+                (x - 5).saturating_sub(label_width),
+                y.saturating_sub(5),
+                &label,
+                FOREGROUND,
+            );
+        }
+    }
+
+    image.draw_text(MARGIN_LEFT - 2 * GLYPH_WIDTH, 0, "s", FOREGROUND);
+}
+
+fn rgb(color: Color) -> [u8; 3] {
+    match color {
+        Color::Rgb(r, g, b) => [r, g, b],
+        _ => FOREGROUND,
+    }
+}
+
+struct Image {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: u32, height: u32) -> Self {
+        Image {
+            width,
+            height,
+            data: BACKGROUND.repeat((width * height) as usize),
+        }
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 3]) {
+        if x < self.width && y < self.height {
+            let index = 3 * (y * self.width + x) as usize;
+            self.data[index..index + 3].copy_from_slice(&color);
+        }
+    }
+
+    fn draw_text(&mut self, x: u32, y: u32, text: &str, color: [u8; 3]) {
+        for (index, c) in text.chars().enumerate() {
+            let left = x + index as u32 * GLYPH_WIDTH;
+            for (dy, line) in glyph(c).iter().enumerate() {
+                for dx in 0..3 {
+                    if line & (0b100 >> dx) == 0 {
+                        continue;
+                    }
+
+                    for sy in 0..FONT_SCALE {
+                        for sx in 0..FONT_SCALE {
+                            self.set_pixel(
+                                left + dx * FONT_SCALE + sx,
+                                y + dy as u32 * FONT_SCALE + sy,
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+
+        Ok(())
+    }
+}
+
+/// 3 x 5 pixel glyphs of the characters used for axis labels
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        'k' => [0b100, 0b101, 0b110, 0b101, 0b101],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'z' => [0b000, 0b111, 0b010, 0b100, 0b111],
+        's' => [0b000, 0b011, 0b100, 0b001, 0b110],
+        _ => [0b000; 5],
+    }
+}
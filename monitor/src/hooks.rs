@@ -0,0 +1,278 @@
+use crate::command;
+use crate::job::Job;
+use crate::state::State;
+
+use chrono::Utc;
+use log::warn;
+
+use std::collections::HashMap;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread;
+use std::time::Duration;
+
+/// Commands run around the passes of the observed satellites
+#[derive(Default)]
+pub struct HookConfig {
+    pub aos: Option<String>,
+    pub tca: Option<String>,
+    pub los: Option<String>,
+    pub job_start: Option<String>,
+    pub job_end: Option<String>,
+    /// Seconds a hook may run
+    pub timeout: u64,
+}
+
+impl HookConfig {
+    pub fn is_empty(&self) -> bool {
+        [
+            &self.aos,
+            &self.tca,
+            &self.los,
+            &self.job_start,
+            &self.job_end,
+        ]
+        .iter()
+        .all(|command| !is_set(command))
+    }
+}
+
+/// An empty hook, e.g. `hook_tca = ""`, is the same as no hook
+fn is_set(command: &Option<String>) -> bool {
+    matches!(command, Some(command) if !command.trim().is_empty())
+}
+
+/// Events of a pass in the order they occur
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum PassEvent {
+    JobStart,
+    Aos,
+    Tca,
+    Los,
+    JobEnd,
+}
+
+impl PassEvent {
+    fn name(self) -> &'static str {
+        match self {
+            PassEvent::JobStart => "job_start",
+            PassEvent::Aos => "aos",
+            PassEvent::Tca => "tca",
+            PassEvent::Los => "los",
+            PassEvent::JobEnd => "job_end",
+        }
+    }
+}
+
+/// Progress of the pass of a running job
+#[derive(Debug, Default)]
+struct Pass {
+    job: u64,
+    /// Last elevation seen, the position is updated every few seconds only
+    elevation: Option<f64>,
+    aos: bool,
+    tca: bool,
+    los: bool,
+}
+
+impl Pass {
+    fn new(job: u64) -> Self {
+        Pass {
+            job,
+            ..Default::default()
+        }
+    }
+
+    /// Follows the elevation of the satellite and returns the event it passed
+    fn update(&mut self, elevation: f64) -> Option<PassEvent> {
+        let previous = self.elevation;
+        self.elevation = Some(elevation);
+
+        if !self.aos {
+            if elevation > 0.0 {
+                self.aos = true;
+                return Some(PassEvent::Aos);
+            }
+        } else if !self.los {
+            if elevation <= 0.0 {
+                self.los = true;
+                return Some(PassEvent::Los);
+            }
+            if !self.tca && matches!(previous, Some(previous) if elevation < previous) {
+                self.tca = true;
+                return Some(PassEvent::Tca);
+            }
+        }
+
+        None
+    }
+
+    /// The events still missing when the job ends
+    fn end(&mut self) -> Vec<PassEvent> {
+        let mut events = vec![];
+        if self.aos && !self.los {
+            self.los = true;
+            events.push(PassEvent::Los);
+        }
+        events.push(PassEvent::JobEnd);
+        events
+    }
+}
+
+/// A hook waiting to be run
+struct Run {
+    name: String,
+    command: String,
+    environment: Vec<(String, String)>,
+}
+
+/// Runs the configured commands at AOS, TCA and LOS of the satellite and at the
+/// start and end of the jobs of all stations. The commands run one after
+/// another in a background thread, their output goes to the log.
+pub struct Hooks {
+    commands: HashMap<PassEvent, String>,
+    /// The pass of the running job of each station
+    passes: HashMap<u64, (Pass, Vec<(String, String)>)>,
+    tx: SyncSender<Run>,
+}
+
+impl Hooks {
+    pub fn new(config: HookConfig) -> Self {
+        let timeout = Duration::new(config.timeout, 0);
+        let commands = vec![
+            (PassEvent::Aos, config.aos),
+            (PassEvent::Tca, config.tca),
+            (PassEvent::Los, config.los),
+            (PassEvent::JobStart, config.job_start),
+            (PassEvent::JobEnd, config.job_end),
+        ]
+        .into_iter()
+        .filter(|(_, command)| is_set(command))
+        .filter_map(|(event, command)| command.map(|command| (event, command)))
+        .collect();
+
+        let (tx, rx) = sync_channel(100);
+        thread::spawn(move || run(rx, timeout));
+
+        Hooks {
+            commands,
+            passes: HashMap::new(),
+            tx,
+        }
+    }
+
+    fn run(&self, event: PassEvent, mut environment: Vec<(String, String)>, job: Option<&Job>) {
+        let command = match self.commands.get(&event) {
+            Some(command) => command.clone(),
+            None => return,
+        };
+
+        environment.push((String::from("SATNOGS_EVENT"), event.name().to_string()));
+        if let Some(job) = job {
+            let sat = job.sat();
+            environment.push((
+                String::from("SATNOGS_AZIMUTH"),
+                format!("{:.1}", sat.az_deg),
+            ));
+            environment.push((
+                String::from("SATNOGS_ELEVATION"),
+                format!("{:.1}", sat.el_deg),
+            ));
+        }
+
+        let name = format!("hook_{}", event.name());
+        let run = Run {
+            name: name.clone(),
+            command,
+            environment,
+        };
+        // never block the ui, a hook which hangs until its timeout delays the others
+        if self.tx.try_send(run).is_err() {
+            warn!("Too many hooks waiting, skipped {}", name);
+        }
+    }
+
+    /// Follows the running jobs of all stations and runs the hooks of the events
+    /// they passed
+    pub fn update(&mut self, state: &State) {
+        let now = Utc::now();
+
+        for station in state.stations.values() {
+            let active = station.active_job(now);
+
+            let previous = self.passes.get(&station.id()).map(|(pass, _)| pass.job);
+            if previous != active.map(Job::id) {
+                if let Some((mut pass, environment)) = self.passes.remove(&station.id()) {
+                    let job = state.job(pass.job);
+                    for event in pass.end() {
+                        self.run(event, environment.clone(), job);
+                    }
+                }
+                if let Some(job) = active {
+                    let environment = command::job_environment(station.id(), job);
+                    self.run(PassEvent::JobStart, environment.clone(), Some(job));
+                    self.passes
+                        .insert(station.id(), (Pass::new(job.id()), environment));
+                }
+            }
+
+            if let (Some(job), Some((pass, environment))) =
+                (active, self.passes.get_mut(&station.id()))
+            {
+                if let Some(event) = pass.update(job.sat().el_deg) {
+                    let environment = environment.clone();
+                    self.run(event, environment, Some(job));
+                }
+            }
+        }
+    }
+}
+
+fn run(rx: Receiver<Run>, timeout: Duration) {
+    while let Ok(run) = rx.recv() {
+        command::run(&run.name, &run.command, &run.environment, timeout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_empty_hooks() {
+        let config = HookConfig {
+            tca: Some(String::new()),
+            los: Some(String::from(" ")),
+            ..Default::default()
+        };
+        assert!(config.is_empty());
+
+        let config = HookConfig {
+            tca: Some(String::new()),
+            los: Some(String::from("logger los")),
+            ..Default::default()
+        };
+        assert!(!config.is_empty());
+    }
+
+    #[test]
+    fn follows_the_pass() {
+        let mut pass = Pass::new(1);
+        let events = [-2.0, 0.5, 10.0, 30.0, 30.0, 29.0, 12.0, -0.1, 5.0]
+            .iter()
+            .filter_map(|elevation| pass.update(*elevation))
+            .collect::<Vec<_>>();
+
+        assert_eq!(events, vec![PassEvent::Aos, PassEvent::Tca, PassEvent::Los]);
+        assert_eq!(pass.end(), vec![PassEvent::JobEnd]);
+    }
+
+    #[test]
+    fn ends_the_pass_with_the_job() {
+        let mut pass = Pass::new(1);
+        assert_eq!(pass.update(5.0), Some(PassEvent::Aos));
+        assert_eq!(pass.end(), vec![PassEvent::Los, PassEvent::JobEnd]);
+
+        let mut pass = Pass::new(2);
+        assert_eq!(pass.end(), vec![PassEvent::JobEnd]);
+    }
+}
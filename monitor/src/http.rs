@@ -0,0 +1,140 @@
+use crate::Result;
+
+use anyhow::bail;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Time a webhook gets to accept the connection, take the request and answer
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The parts of a http request the monitor's endpoints look at
+pub struct Request {
+    pub method: String,
+    pub path: String,
+}
+
+impl Request {
+    /// Reads the request line and skips the headers
+    pub fn read(stream: &TcpStream) -> Result<Self> {
+        stream.set_read_timeout(Some(Duration::new(5, 0)))?;
+        let mut reader = BufReader::new(stream);
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => Ok(Request {
+                method: method.to_string(),
+                // the query string isn't used by any endpoint
+                path: path.split('?').next().unwrap_or_default().to_string(),
+            }),
+            _ => bail!("invalid http request: {:?}", line.trim()),
+        }
+    }
+}
+
+/// Writes a complete response and lets the client close the connection
+pub fn respond(mut stream: &TcpStream, status: &str, content_type: &str, body: &str) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+
+    Ok(())
+}
+
+/// Posts `body` to a `http://` URL and checks for a successful status code
+pub fn post(url: &str, content_type: &str, body: &str) -> Result<()> {
+    let (host, path) = parse_url(url)?;
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let mut stream = connect(&address)?;
+    stream.set_read_timeout(Some(POST_TIMEOUT))?;
+    stream.set_write_timeout(Some(POST_TIMEOUT))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        host,
+        content_type,
+        body.len(),
+        body
+    )?;
+
+    let mut status = String::new();
+    BufReader::new(&stream).read_line(&mut status)?;
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => bail!("{} answered {:?}", url, status.trim()),
+    }
+}
+
+/// Connects to the first address of a host which answers within the timeout, an
+/// unreachable host must not stall the caller for minutes
+fn connect(address: &str) -> Result<TcpStream> {
+    let mut error = None;
+    for socket_address in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&socket_address, POST_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => error = Some(err),
+        }
+    }
+
+    match error {
+        Some(err) => Err(err.into()),
+        None => bail!("no address found for {}", address),
+    }
+}
+
+/// Splits a `http://` URL into host with optional port and path
+fn parse_url(url: &str) -> Result<(&str, &str)> {
+    if url.starts_with("https://") {
+        bail!(
+            "https isn't supported for {}, use a command like curl instead",
+            url
+        );
+    }
+    let rest = match url.strip_prefix("http://") {
+        Some(rest) => rest,
+        None => bail!("invalid http URL: {}", url),
+    };
+
+    Ok(match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_urls() {
+        assert_eq!(
+            parse_url("http://localhost:8080/hooks/pass").unwrap(),
+            ("localhost:8080", "/hooks/pass")
+        );
+        assert_eq!(
+            parse_url("http://example.org").unwrap(),
+            ("example.org", "/")
+        );
+        assert!(parse_url("https://example.org/hook").is_err());
+        assert!(parse_url("example.org/hook").is_err());
+    }
+}
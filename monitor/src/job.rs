@@ -35,6 +35,12 @@ impl Job {
         self.job.frequency as f64 / 1_000_000.0
     }
 
+    /// Frequency of the transmitter as received with the current range rate
+    pub fn doppler_frequency_mhz(&self) -> f64 {
+        const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
+        self.frequency_mhz() * (1.0 - self.sat().range_rate_km_sec / SPEED_OF_LIGHT_KM_S)
+    }
+
     pub fn vessel_name(&self) -> &str {
         &self.vessel.name()
     }
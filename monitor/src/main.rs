@@ -1,30 +1,59 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use satnogs_network_client::Client;
 use std::thread;
 use systemstat::{Platform, System};
 
+mod api;
+mod command;
 mod event;
+mod export;
+mod hooks;
+mod http;
 mod job;
 mod logger;
+mod metrics;
+mod mqtt;
+mod notifications;
+mod recordings;
+mod replay;
+mod rigctld_client;
 mod rotctld_client;
 mod satnogs;
 mod settings;
+mod spectrum;
 mod state;
 mod station;
+mod status;
+mod sun;
 mod sysinfo;
 mod ui;
 mod vessel;
+mod viewer;
 mod waterfall;
 mod widgets;
 
+use self::api::Api;
 use self::event::Event;
+use self::hooks::{HookConfig, Hooks};
+use self::metrics::Metrics;
+use self::mqtt::{MqttConfig, MqttPublisher, MqttTopics};
+use self::notifications::{Notifier, NotifyConfig};
+use self::recordings::RecordingWatcher;
+use self::replay::{Player, Recorder};
+use self::rigctld_client::RigCtldClient;
 use self::rotctld_client::RotCtldClient;
 use self::settings::{Settings, StationConfig};
 use self::station::Station;
-use self::sysinfo::SysInfo;
+use self::sysinfo::{MemInfo, SysInfo};
+use self::viewer::Viewer;
 use self::waterfall::WaterfallWatcher;
+use self::widgets::ColorMode;
 
-use clap::{ArgGroup, Parser};
+use clap::{ArgGroup, Parser, Subcommand};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 /// Monitors the current and future jobs of SatNOGS ground stations.
 #[derive(Parser, Debug)]
@@ -33,6 +62,7 @@ use clap::{ArgGroup, Parser};
     about,
     long_about = None,
     max_term_width = 100,
+    subcommand_negates_reqs = true,
     group(
         ArgGroup::new("stations")
             .args(["local_station", "station"])
@@ -72,6 +102,14 @@ struct Cli {
     #[arg(long = "data-path", value_name = "PATH")]
     data_path: Option<String>,
 
+    /// Enables radio monitoring if set to a rigctld address
+    #[arg(long = "rigctld-address", value_name = "IP:PORT")]
+    rigctld_address: Option<String>,
+
+    /// Polls the radio frequency every INTERVAL seconds
+    #[arg(long = "rigctld-interval", value_name = "INTERVAL")]
+    rigctld_interval: Option<u64>,
+
     /// Enables rotator monitoring if set to a rotctld address
     #[arg(long = "rotctld-address", value_name = "IP:PORT")]
     rotctld_address: Option<String>,
@@ -80,6 +118,22 @@ struct Cli {
     #[arg(long = "rotctld-interval", value_name = "INTERVAL")]
     rotctld_interval: Option<u64>,
 
+    /// Serves the monitor state as JSON on http://IP:PORT/api/
+    #[arg(long = "http-address", value_name = "IP:PORT")]
+    http_address: Option<String>,
+
+    /// Serves Prometheus metrics on http://IP:PORT/metrics
+    #[arg(long = "metrics-address", value_name = "IP:PORT")]
+    metrics_address: Option<String>,
+
+    /// Publishes the station state to the MQTT broker at HOST:PORT
+    #[arg(long = "mqtt-address", value_name = "HOST:PORT")]
+    mqtt_address: Option<String>,
+
+    /// Posts notifications about passes and station problems to URL
+    #[arg(long = "notify-webhook", value_name = "URL")]
+    notify_webhook: Option<String>,
+
     /// Sets the lower dB bound of the spectrum and waterfall plot
     #[arg(long = "db-min", value_name = "DB")]
     db_min: Option<f32>,
@@ -103,6 +157,51 @@ struct Cli {
     /// Polls the network for new jobs every SECONDS
     #[arg(long = "job-update-interval", value_name = "SECONDS")]
     job_update_interval: Option<u64>,
+
+    /// Runs without terminal ui and writes the station status as JSON lines
+    #[arg(long = "headless")]
+    headless: bool,
+
+    /// Writes the status in headless mode to FILE instead of stdout
+    #[arg(long = "status-file", value_name = "FILE")]
+    status_file: Option<String>,
+
+    /// Records the jobs, rotator, radio, system and waterfall events to FILE
+    #[arg(long = "record", value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Displays waterfall files recorded by the SatNOGS client
+    View {
+        /// Waterfall files or directories containing waterfall files
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Renders waterfall files recorded by the SatNOGS client to PNG images
+    Export {
+        /// Waterfall files or directories containing waterfall files
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Writes the images to DIR instead of next to the waterfall files
+        #[arg(long = "output", value_name = "DIR")]
+        output: Option<PathBuf>,
+    },
+    /// Replays events recorded with --record without connecting to the network
+    Replay {
+        /// Recording to replay
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Replays the events SPEED times faster than recorded
+        #[arg(long = "speed", value_name = "SPEED", default_value_t = 1.0)]
+        speed: f64,
+    },
 }
 
 fn main() -> Result<()> {
@@ -110,7 +209,25 @@ fn main() -> Result<()> {
 }
 
 fn run() -> Result<()> {
-    let settings = settings()?;
+    let mut cli = Cli::parse();
+    let command = cli.command.take();
+    let record = cli.record.take();
+    let headless = cli.headless;
+    let settings = settings(cli, command.is_none())?;
+
+    match command {
+        Some(Command::View { paths }) => Viewer::new(&paths, settings)?.run(),
+        Some(Command::Export { paths, output }) => export::export_files(
+            &paths,
+            output.as_deref(),
+            [settings.ui.db_min, settings.ui.db_max],
+        ),
+        Some(Command::Replay { file, speed }) => replay(settings, &file, speed),
+        None => monitor(settings, record.as_deref(), headless),
+    }
+}
+
+fn monitor(settings: Settings, record: Option<&Path>, headless: bool) -> Result<()> {
     // get the station info from the network
     let mut client = Client::new(&settings.api_endpoint)?;
 
@@ -126,9 +243,29 @@ fn run() -> Result<()> {
 
     state.update_ground_tracks(settings.ui.ground_track_num);
 
-    let data_path = settings.data_path.clone();
+    // the satnogs-client data paths and the stations writing into them
+    let mut data_paths: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    if let Some(data_path) = &settings.data_path {
+        data_paths.entry(data_path.clone()).or_default();
+    }
+    for station in &settings.stations {
+        let data_path = match (&station.data_path, &settings.data_path) {
+            (Some(data_path), _) => data_path,
+            (None, Some(data_path)) if station.local => data_path,
+            _ => continue,
+        };
+        data_paths
+            .entry(data_path.clone())
+            .or_default()
+            .push(station.satnogs_id);
+    }
+
+    let waterfall_max_rows = settings.waterfall_max_rows;
+    let rigctld_address = settings.rigctld_address.clone();
+    let rigctld_interval = settings.rigctld_interval;
     let rotctld_address = settings.rotctld_address.clone();
     let rotctld_interval = settings.rotctld_interval.clone();
+    let color_mode = settings.ui.color_mode;
 
     let local_stations: Vec<_> = settings
         .stations
@@ -136,8 +273,103 @@ fn run() -> Result<()> {
         .filter(|sc| sc.local)
         .map(|sc| sc.satnogs_id)
         .collect();
-    let tui = ui::Ui::new(settings, client, state)?;
+    let http_address = settings.http_address.clone();
+    let metrics_address = settings.metrics_address.clone();
+    let metrics = match &metrics_address {
+        Some(address) => Some(Metrics::serve(address)?),
+        None => None,
+    };
+
+    let mqtt = settings.mqtt_address.as_ref().map(|address| MqttConfig {
+        address: address.clone(),
+        client_id: settings.mqtt_client_id.clone(),
+        credentials: settings
+            .mqtt_username
+            .clone()
+            .map(|username| (username, settings.mqtt_password.clone().unwrap_or_default())),
+        retain: settings.mqtt_retain,
+        topic_prefix: settings.mqtt_topic_prefix.clone(),
+        topics: MqttTopics {
+            job: settings.mqtt_topic_job.clone(),
+            rotator: settings.mqtt_topic_rotator.clone(),
+            satellite: settings.mqtt_topic_satellite.clone(),
+            status: settings.mqtt_topic_status.clone(),
+            sysinfo: settings.mqtt_topic_sysinfo.clone(),
+        },
+    });
+
+    let notify = if settings.notify_webhook.is_some() || settings.notify_command.is_some() {
+        Some(NotifyConfig {
+            before: settings.notify_before,
+            command: settings.notify_command.clone(),
+            events: settings.notify_events.clone(),
+            timeout: settings.notify_timeout,
+            webhook: settings.notify_webhook.clone(),
+        })
+    } else {
+        None
+    };
+
+    let hooks = HookConfig {
+        aos: settings.hook_aos.clone(),
+        tca: settings.hook_tca.clone(),
+        los: settings.hook_los.clone(),
+        job_start: settings.hook_job_start.clone(),
+        job_end: settings.hook_job_end.clone(),
+        timeout: settings.hook_timeout,
+    };
+
+    let recorder = match record {
+        Some(path) => {
+            let stations = state.stations.values().map(|station| &station.info);
+            Some(Recorder::create(path, &stations.collect::<Vec<_>>())?)
+        }
+        None => None,
+    };
+
+    let mut tui = if headless {
+        let output: Box<dyn Write> = match &settings.status_file {
+            Some(path) => Box::new(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("couldn't open status file {}", path))?,
+            ),
+            None => Box::new(io::stdout()),
+        };
+        ui::Ui::headless(settings, state, output)
+    } else {
+        ui::Ui::new(settings, state)?
+    };
+    if let Some(recorder) = recorder {
+        tui = tui.with_recorder(recorder);
+    }
+    if let Some(metrics) = metrics {
+        tui = tui.with_metrics(metrics);
+    }
+    if let Some(address) = &http_address {
+        let api = Api::serve(address, tui.sender())?;
+        tui = tui.with_api(api);
+    }
     log::set_boxed_logger(Box::new(logger::Logger::new(tui.sender())))?;
+    // the publisher logs its connection state, so it's started after the logger
+    if let Some(mqtt) = mqtt {
+        tui = tui.with_mqtt(MqttPublisher::new(mqtt));
+    }
+    if let Some(notify) = notify {
+        tui = tui.with_notifier(Notifier::new(notify));
+    }
+    if !hooks.is_empty() {
+        tui = tui.with_hooks(Hooks::new(hooks));
+    }
+    log::info!("Waterfall color mode: {}", color_mode.name());
+    if let Some(address) = metrics_address {
+        log::info!("Serving metrics on http://{}/metrics", address);
+    }
+    if let Some(address) = http_address {
+        log::info!("Serving the API on http://{}/api/", address);
+    }
 
     if !local_stations.is_empty() {
         let tx = tui.sender();
@@ -154,18 +386,36 @@ fn run() -> Result<()> {
         });
     }
 
-    // watch for waterfall if enabled
-    if let Some(data_path) = data_path {
+    // watch for waterfalls if enabled
+    for (data_path, stations) in &data_paths {
         log::info!("Starting waterfall watcher for {}", data_path);
 
+        // waterfalls in a shared data path are assigned to a station by their job
+        let station = match stations.as_slice() {
+            [station] => Some(*station),
+            _ => None,
+        };
+
         let tx = tui.sender();
-        let mut waterfall_watcher = WaterfallWatcher::new(&data_path, tx)?;
+        let mut waterfall_watcher =
+            WaterfallWatcher::new(data_path, station, waterfall_max_rows, tx)?;
 
         thread::spawn(move || {
             if let Err(err) = waterfall_watcher.run() {
                 log::error!("Waterfall watcher stopped with error: {}", err);
             }
         });
+    }
+
+    if !data_paths.is_empty() {
+        let paths = data_paths.keys().map(String::as_str).collect::<Vec<_>>();
+        let mut recording_watcher = RecordingWatcher::new(&paths, tui.sender());
+
+        thread::spawn(move || {
+            if let Err(err) = recording_watcher.run() {
+                log::error!("Recording watcher stopped with error: {}", err);
+            }
+        });
     };
 
     if let Some(rotctld_address) = rotctld_address {
@@ -196,6 +446,69 @@ fn run() -> Result<()> {
         });
     }
 
+    if let Some(rigctld_address) = rigctld_address {
+        log::info!("Connecting to rigctld at {}", rigctld_address);
+
+        let tx = tui.sender();
+        let mut client = RigCtldClient::new(&rigctld_address)?;
+
+        log::info!(
+            "Connected to rigctld at {} polling every {} seconds",
+            rigctld_address,
+            rigctld_interval
+        );
+
+        thread::spawn(move || {
+            while let Ok(status) = client.status() {
+                log::trace!("RigCtl: {} Hz {}", status.frequency, status.mode);
+                match tx.send(Event::RigStatus(status)) {
+                    Ok(_) => thread::sleep(std::time::Duration::new(rigctld_interval, 0)),
+                    Err(e) => {
+                        log::error!("Failed to send rig status: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            log::error!("Lost connection to rigctld");
+        });
+    }
+
+    tui.run()
+}
+
+fn replay(settings: Settings, path: &Path, speed: f64) -> Result<()> {
+    // NaN isn't finite either
+    if !speed.is_finite() || speed <= 0.0 {
+        bail!("Option speed out of bound. Must be a number greater than 0.0")
+    }
+
+    let (player, stations) = Player::open(path, speed)?;
+    if stations.is_empty() {
+        bail!("no station in recording {}", path.display());
+    }
+
+    let mut state = state::State::new();
+    for info in stations {
+        if state.active_station == 0 {
+            state.active_station = info.id;
+        }
+        state.add_station(Station::new(info));
+    }
+
+    state.update_ground_tracks(settings.ui.ground_track_num);
+
+    let tui = ui::Ui::new(settings, state)?.offline();
+    log::set_boxed_logger(Box::new(logger::Logger::new(tui.sender())))?;
+    log::info!("Replaying {} at {}x speed", path.display(), speed);
+
+    let tx = tui.sender();
+    let path = path.to_path_buf();
+    thread::spawn(move || match player.run(tx) {
+        Ok(()) => log::info!("Replay of {} finished", path.display()),
+        Err(err) => log::error!("Replay of {} stopped: {:#}", path.display(), err),
+    });
+
     tui.run()
 }
 
@@ -205,18 +518,25 @@ fn get_sysinfo() -> Result<SysInfo> {
     thread::sleep(std::time::Duration::new(1, 0));
 
     Ok(SysInfo {
-        cpu_load: cpu_load.and_then(|load| load.done()).ok(),
+        cpu_idle: cpu_load
+            .and_then(|load| load.done())
+            .map(|cores| cores.iter().map(|core| core.idle).collect())
+            .ok(),
         cpu_temp: sys.cpu_temp().ok(),
-        mem: sys.memory().ok(),
+        mem: sys
+            .memory()
+            .map(|mem| MemInfo {
+                total: mem.total.as_u64(),
+                free: mem.free.as_u64(),
+            })
+            .ok(),
         uptime: sys.uptime().ok(),
     })
 }
 
 /// Generates the internal settings representation for the app. CLI options will
 /// override the options loaded from config files.
-fn settings() -> Result<Settings> {
-    let cli = Cli::parse();
-
+fn settings(cli: Cli, require_stations: bool) -> Result<Settings> {
     // Check floating-point-ranges. ToDo: Once clap::value_parser implements floats, check it there
     if let Some(value) = cli.db_min {
         if value < -200.0 || value > 0.0 {
@@ -271,7 +591,7 @@ fn settings() -> Result<Settings> {
         }
     }
 
-    if settings.stations.is_empty() {
+    if require_stations && settings.stations.is_empty() {
         bail!("no station provided");
     }
 
@@ -281,6 +601,14 @@ fn settings() -> Result<Settings> {
 
     settings.ui.ground_track_num = cli.orbits;
 
+    if let Some(addr) = cli.rigctld_address {
+        settings.rigctld_address = Some(addr);
+    }
+
+    if let Some(i) = cli.rigctld_interval {
+        settings.rigctld_interval = i;
+    }
+
     if let Some(addr) = cli.rotctld_address {
         settings.rotctld_address = Some(addr);
     }
@@ -289,6 +617,26 @@ fn settings() -> Result<Settings> {
         settings.rotctld_interval = i;
     }
 
+    if let Some(addr) = cli.http_address {
+        settings.http_address = Some(addr);
+    }
+
+    if let Some(addr) = cli.metrics_address {
+        settings.metrics_address = Some(addr);
+    }
+
+    if let Some(addr) = cli.mqtt_address {
+        settings.mqtt_address = Some(addr);
+    }
+
+    if let Some(url) = cli.notify_webhook {
+        settings.notify_webhook = Some(url);
+    }
+
+    if let Some(path) = cli.status_file {
+        settings.status_file = Some(path);
+    }
+
     if let Some(path) = cli.data_path {
         settings.data_path = Some(path);
     }
@@ -309,6 +657,10 @@ fn settings() -> Result<Settings> {
         );
     }
 
+    if settings.ui.color_mode == ColorMode::Auto {
+        settings.ui.color_mode = ColorMode::detect();
+    }
+
     settings.ui.spectrum_plot |= cli.spectrum;
     settings.ui.waterfall |= cli.waterfall;
 
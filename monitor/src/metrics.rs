@@ -0,0 +1,359 @@
+use crate::http::{self, Request};
+use crate::state::State;
+use crate::status::Status;
+use crate::Result;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
+
+use std::fmt::Write as _;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Collects the numbers of the monitor and serves them in the Prometheus text
+/// exposition format on `/metrics`
+pub struct Metrics {
+    /// The rendered metrics shared with the http thread
+    exposition: Arc<Mutex<String>>,
+    last_waterfall_row: Option<DateTime<Utc>>,
+    waterfall_rows: u64,
+}
+
+impl Metrics {
+    /// Starts serving the metrics on `address`
+    pub fn serve(address: &str) -> Result<Self> {
+        let listener = TcpListener::bind(address)
+            .with_context(|| format!("couldn't listen for metrics requests on {}", address))?;
+        let exposition = Arc::new(Mutex::new(String::new()));
+
+        let shared = exposition.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        debug!("Failed to accept metrics connection: {}", err);
+                        continue;
+                    }
+                };
+
+                // a client which never sends its request mustn't stall the scrapes
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    if let Err(err) = respond(stream, &shared) {
+                        debug!("Failed to answer metrics request: {}", err);
+                    }
+                });
+            }
+            warn!("Metrics endpoint stopped");
+        });
+
+        Ok(Metrics {
+            exposition,
+            last_waterfall_row: None,
+            waterfall_rows: 0,
+        })
+    }
+
+    pub fn waterfall_row(&mut self) {
+        self.waterfall_rows += 1;
+        self.last_waterfall_row = Some(Utc::now());
+    }
+
+    /// Renders the metrics of the current state for the next request
+    pub fn update(&self, state: &State) {
+        let text = self.render(&Status::new(state));
+        *self.exposition.lock().unwrap() = text;
+    }
+
+    fn render(&self, status: &Status) -> String {
+        let mut exposition = Exposition::default();
+
+        exposition.family(
+            "satnogs_monitor_station_status",
+            "gauge",
+            "Station status as reported by the network, 1 for the current status",
+        );
+        for station in &status.stations {
+            let id = station.id.to_string();
+            for value in &["online", "testing", "offline"] {
+                exposition.sample(
+                    "satnogs_monitor_station_status",
+                    &[("station", &id), ("name", station.name), ("status", value)],
+                    if station.status == *value { 1.0 } else { 0.0 },
+                );
+            }
+        }
+
+        exposition.family(
+            "satnogs_monitor_station_last_seen_timestamp_seconds",
+            "gauge",
+            "Time the network heard from the station the last time",
+        );
+        for station in &status.stations {
+            if let Some(last_seen) = station.last_seen {
+                exposition.sample(
+                    "satnogs_monitor_station_last_seen_timestamp_seconds",
+                    &[("station", &station.id.to_string())],
+                    last_seen.timestamp() as f64,
+                );
+            }
+        }
+
+        exposition.family(
+            "satnogs_monitor_station_jobs",
+            "gauge",
+            "Number of upcoming and running jobs",
+        );
+        for station in &status.stations {
+            exposition.sample(
+                "satnogs_monitor_station_jobs",
+                &[("station", &station.id.to_string())],
+                station.jobs as f64,
+            );
+        }
+
+        exposition.family(
+            "satnogs_monitor_station_observing",
+            "gauge",
+            "1 if the station is observing right now",
+        );
+        for station in &status.stations {
+            exposition.sample(
+                "satnogs_monitor_station_observing",
+                &[("station", &station.id.to_string())],
+                if station.active_job.is_some() {
+                    1.0
+                } else {
+                    0.0
+                },
+            );
+        }
+
+        let sys_infos = status
+            .stations
+            .iter()
+            .map(|station| (station.id.to_string(), station.sys_info))
+            .collect::<Vec<_>>();
+
+        exposition.family(
+            "satnogs_monitor_cpu_load_percent",
+            "gauge",
+            "CPU load of the station host averaged over all cores",
+        );
+        for (id, sys_info) in &sys_infos {
+            if let Some(cpu_idle) = sys_info.cpu_idle.as_ref().filter(|idle| !idle.is_empty()) {
+                let idle = cpu_idle.iter().sum::<f32>() / cpu_idle.len() as f32;
+                exposition.sample(
+                    "satnogs_monitor_cpu_load_percent",
+                    &[("station", id)],
+                    f64::from(100.0 - idle * 100.0),
+                );
+            }
+        }
+
+        exposition.family(
+            "satnogs_monitor_cpu_temperature_celsius",
+            "gauge",
+            "CPU temperature of the station host",
+        );
+        for (id, sys_info) in &sys_infos {
+            if let Some(temp) = sys_info.cpu_temp {
+                exposition.sample(
+                    "satnogs_monitor_cpu_temperature_celsius",
+                    &[("station", id)],
+                    f64::from(temp),
+                );
+            }
+        }
+
+        exposition.family(
+            "satnogs_monitor_memory_total_bytes",
+            "gauge",
+            "Memory of the station host",
+        );
+        for (id, sys_info) in &sys_infos {
+            if let Some(mem) = sys_info.mem {
+                exposition.sample(
+                    "satnogs_monitor_memory_total_bytes",
+                    &[("station", id)],
+                    mem.total as f64,
+                );
+            }
+        }
+
+        exposition.family(
+            "satnogs_monitor_memory_free_bytes",
+            "gauge",
+            "Free memory of the station host",
+        );
+        for (id, sys_info) in &sys_infos {
+            if let Some(mem) = sys_info.mem {
+                exposition.sample(
+                    "satnogs_monitor_memory_free_bytes",
+                    &[("station", id)],
+                    mem.free as f64,
+                );
+            }
+        }
+
+        // rotator and rig belong to the active station
+        let station = status.active_station.to_string();
+        if let Some(rotator) = &status.rotator {
+            exposition.family(
+                "satnogs_monitor_rotator_position_degrees",
+                "gauge",
+                "Rotator position reported by rotctld",
+            );
+            exposition.sample(
+                "satnogs_monitor_rotator_position_degrees",
+                &[("station", &station), ("axis", "azimuth")],
+                rotator.azimuth,
+            );
+            exposition.sample(
+                "satnogs_monitor_rotator_position_degrees",
+                &[("station", &station), ("axis", "elevation")],
+                rotator.elevation,
+            );
+
+            if let (Some(azimuth), Some(elevation)) =
+                (rotator.azimuth_error, rotator.elevation_error)
+            {
+                exposition.family(
+                    "satnogs_monitor_rotator_error_degrees",
+                    "gauge",
+                    "Deviation of the rotator from the satellite of the next job",
+                );
+                exposition.sample(
+                    "satnogs_monitor_rotator_error_degrees",
+                    &[("station", &station), ("axis", "azimuth")],
+                    azimuth,
+                );
+                exposition.sample(
+                    "satnogs_monitor_rotator_error_degrees",
+                    &[("station", &station), ("axis", "elevation")],
+                    elevation,
+                );
+            }
+        }
+
+        if let Some(rig) = status.rig {
+            exposition.family(
+                "satnogs_monitor_rig_frequency_hertz",
+                "gauge",
+                "VFO frequency reported by rigctld",
+            );
+            exposition.sample(
+                "satnogs_monitor_rig_frequency_hertz",
+                &[("station", &station)],
+                rig.frequency,
+            );
+        }
+
+        exposition.family(
+            "satnogs_monitor_waterfall_rows_total",
+            "counter",
+            "Waterfall rows read from the satnogs-client data paths",
+        );
+        exposition.sample(
+            "satnogs_monitor_waterfall_rows_total",
+            &[],
+            self.waterfall_rows as f64,
+        );
+
+        if let Some(last_row) = self.last_waterfall_row {
+            exposition.family(
+                "satnogs_monitor_waterfall_last_row_timestamp_seconds",
+                "gauge",
+                "Time the last waterfall row was read",
+            );
+            exposition.sample(
+                "satnogs_monitor_waterfall_last_row_timestamp_seconds",
+                &[],
+                last_row.timestamp() as f64,
+            );
+        }
+
+        exposition.text
+    }
+}
+
+/// Builder for the text exposition format
+#[derive(Default)]
+struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.text.push_str(name);
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape(value)))
+                .collect::<Vec<_>>();
+            let _ = write!(self.text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.text, " {}", value);
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Answers a single http request, only `GET /metrics` is supported
+fn respond(stream: TcpStream, exposition: &Mutex<String>) -> Result<()> {
+    let request = Request::read(&stream)?;
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/metrics") => {
+            http::respond(&stream, "200 OK", CONTENT_TYPE, &exposition.lock().unwrap())
+        }
+        ("GET", _) => http::respond(&stream, "404 Not Found", "text/plain", "not found\n"),
+        _ => http::respond(
+            &stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_samples() {
+        let mut exposition = Exposition::default();
+        exposition.family("satnogs_test", "gauge", "Test metric");
+        exposition.sample("satnogs_test", &[], 1.5);
+        exposition.sample(
+            "satnogs_test",
+            &[("station", "175"), ("name", "Roof \"East\"")],
+            2.0,
+        );
+
+        assert_eq!(
+            exposition.text,
+            "# HELP satnogs_test Test metric\n\
+             # TYPE satnogs_test gauge\n\
+             satnogs_test 1.5\n\
+             satnogs_test{station=\"175\",name=\"Roof \\\"East\\\"\"} 2\n"
+        );
+    }
+}
@@ -0,0 +1,535 @@
+use crate::event::Event;
+use crate::state::State;
+use crate::status::{JobInfo, Status};
+use crate::Result;
+
+use anyhow::bail;
+use log::{debug, info, warn};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Seconds the broker waits for a packet before it drops the connection
+const KEEP_ALIVE: u16 = 60;
+
+/// Seconds between two connection attempts
+const RECONNECT_INTERVAL: u64 = 10;
+
+/// Minimal MQTT 3.1.1 client, publishes with QoS 0 only
+pub struct MqttClient {
+    stream: TcpStream,
+}
+
+impl MqttClient {
+    pub fn connect(
+        address: &str,
+        client_id: &str,
+        credentials: Option<(&str, &str)>,
+    ) -> Result<Self> {
+        let mut stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(Duration::new(5, 0)))?;
+
+        // protocol name, level 4 (3.1.1), connect flags and keep alive
+        let mut packet = vec![0x00, 0x04, b'M', b'Q', b'T', b'T', 0x04];
+        let mut flags = 0x02; // clean session
+        if credentials.is_some() {
+            flags |= 0x80 | 0x40;
+        }
+        packet.push(flags);
+        packet.extend_from_slice(&KEEP_ALIVE.to_be_bytes());
+        put_string(&mut packet, client_id);
+        if let Some((username, password)) = credentials {
+            put_string(&mut packet, username);
+            put_string(&mut packet, password);
+        }
+        stream.write_all(&encode(0x10, &packet))?;
+
+        let mut connack = [0u8; 4];
+        stream.read_exact(&mut connack)?;
+        if connack[0] != 0x20 {
+            bail!("unexpected answer from MQTT broker: {:#04x}", connack[0]);
+        }
+        if connack[3] != 0 {
+            bail!("MQTT broker refused connection with code {}", connack[3]);
+        }
+
+        Ok(MqttClient { stream })
+    }
+
+    pub fn publish(&mut self, topic: &str, payload: &[u8], retain: bool) -> Result<()> {
+        let mut packet = vec![];
+        put_string(&mut packet, topic);
+        packet.extend_from_slice(payload);
+        let header = if retain { 0x31 } else { 0x30 };
+
+        Ok(self.stream.write_all(&encode(header, &packet))?)
+    }
+
+    /// Keeps the connection alive and checks that the broker still answers
+    pub fn ping(&mut self) -> Result<()> {
+        self.stream.write_all(&[0xc0, 0x00])?;
+
+        let mut pingresp = [0u8; 2];
+        self.stream.read_exact(&mut pingresp)?;
+        if pingresp != [0xd0, 0x00] {
+            bail!("unexpected answer from MQTT broker: {:#04x}", pingresp[0]);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for MqttClient {
+    fn drop(&mut self) {
+        let _ = self.stream.write_all(&[0xe0, 0x00]);
+    }
+}
+
+fn put_string(packet: &mut Vec<u8>, value: &str) {
+    packet.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    packet.extend_from_slice(value.as_bytes());
+}
+
+/// Prefixes a packet with its fixed header
+fn encode(header: u8, packet: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![header];
+    let mut length = packet.len();
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        encoded.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+    encoded.extend_from_slice(packet);
+
+    encoded
+}
+
+/// Topics of the messages relative to the prefix, `{station}` is replaced by the
+/// id of the station
+#[derive(Clone)]
+pub struct MqttTopics {
+    pub job: String,
+    pub rotator: String,
+    pub satellite: String,
+    pub status: String,
+    pub sysinfo: String,
+}
+
+pub struct MqttConfig {
+    pub address: String,
+    pub client_id: String,
+    pub credentials: Option<(String, String)>,
+    pub retain: bool,
+    pub topic_prefix: String,
+    pub topics: MqttTopics,
+}
+
+/// State of the connection to the broker shared with the background thread
+#[derive(Default)]
+struct Connection {
+    connected: AtomicBool,
+    /// Incremented on every connect, the full state is published again after
+    /// the broker was away
+    generation: AtomicUsize,
+}
+
+/// The observing state of a station last published
+#[derive(PartialEq)]
+struct StationState {
+    status: &'static str,
+    job: Option<u64>,
+}
+
+/// A running job, kept to publish its end after it left the job list
+struct RunningJob {
+    id: u64,
+    info: Value,
+}
+
+/// Publishes the station state to a MQTT broker. The messages are sent from a
+/// background thread which reconnects if the broker goes away, nothing but the
+/// end of jobs is queued while there is no connection.
+pub struct MqttPublisher {
+    connection: Arc<Connection>,
+    /// End events of jobs which couldn't be published yet, by station
+    ended: Vec<(u64, Value)>,
+    generation: usize,
+    retain: bool,
+    running: HashMap<u64, RunningJob>,
+    stations: HashMap<u64, StationState>,
+    topic_prefix: String,
+    topics: MqttTopics,
+    tx: SyncSender<(String, Vec<u8>, bool)>,
+}
+
+impl MqttPublisher {
+    pub fn new(config: MqttConfig) -> Self {
+        let (tx, rx) = sync_channel(100);
+        let connection = Arc::new(Connection::default());
+        let retain = config.retain;
+        let topic_prefix = config.topic_prefix.trim_end_matches('/').to_string();
+        let topics = config.topics.clone();
+
+        let worker_connection = connection.clone();
+        thread::spawn(move || run(config, rx, &worker_connection));
+
+        MqttPublisher {
+            connection,
+            ended: Vec::new(),
+            generation: 0,
+            retain,
+            running: HashMap::new(),
+            stations: HashMap::new(),
+            topic_prefix,
+            topics,
+            tx,
+        }
+    }
+
+    /// Queues a message, returns whether it was queued
+    fn publish<T: Serialize>(&self, topic: &str, payload: &T, retain: bool) -> bool {
+        if !self.connection.connected.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let topic = if self.topic_prefix.is_empty() {
+            topic.to_string()
+        } else {
+            format!("{}/{}", self.topic_prefix, topic)
+        };
+        match serde_json::to_vec(payload) {
+            Ok(payload) => {
+                // never block the ui, drop the message if the broker can't keep up
+                if self.tx.try_send((topic, payload, retain)).is_err() {
+                    debug!("MQTT queue full, dropped message");
+                    return false;
+                }
+                true
+            }
+            Err(err) => {
+                warn!("Failed to serialize MQTT message: {}", err);
+                false
+            }
+        }
+    }
+
+    /// Publishes the end of a job or keeps it for the next update
+    fn publish_end(&mut self, station: u64, info: Value) {
+        let published = self.publish(
+            &station_topic(&self.topics.job, station),
+            &json!({ "event": "end", "job": info }),
+            false,
+        );
+        if !published {
+            self.ended.push((station, info));
+        }
+    }
+
+    /// Publishes the rotator position and system infos as they arrive
+    pub fn handle_event(&self, event: &Event) {
+        match event {
+            Event::RotatorPosition(azimuth, elevation) => {
+                self.publish(
+                    &self.topics.rotator,
+                    &json!({ "azimuth": azimuth, "elevation": elevation }),
+                    self.retain,
+                );
+            }
+            Event::SystemInfo(stations, sys_info) => {
+                for id in stations {
+                    self.publish(
+                        &station_topic(&self.topics.sysinfo, *id),
+                        sys_info,
+                        self.retain,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Publishes changes of the station status, the start and end of jobs and
+    /// the position of the satellite of the running jobs
+    pub fn update(&mut self, state: &State, satellites: bool) {
+        // the broker lost the state which wasn't retained, publish it again
+        let generation = self.connection.generation.load(Ordering::Relaxed);
+        if generation != self.generation {
+            self.generation = generation;
+            self.stations.clear();
+        }
+
+        // the end of a job is only published once, keep it until it's sent
+        for (station, info) in std::mem::take(&mut self.ended) {
+            self.publish_end(station, info);
+        }
+
+        let status = Status::new(state);
+
+        for station in &status.stations {
+            let current = StationState {
+                status: station.status,
+                job: station.active_job.as_ref().map(|job| job.id),
+            };
+
+            let previous = self.running.get(&station.id).map(|running| running.id);
+            if previous != current.job {
+                if let Some(running) = self.running.remove(&station.id) {
+                    self.publish_end(station.id, running.info);
+                }
+                if let Some(job) = &station.active_job {
+                    let running = RunningJob {
+                        id: job.id,
+                        info: serde_json::to_value(job).unwrap_or_default(),
+                    };
+                    self.publish(
+                        &station_topic(&self.topics.job, station.id),
+                        &json!({ "event": "start", "job": running.info }),
+                        false,
+                    );
+                    self.running.insert(station.id, running);
+                }
+            }
+
+            if let (true, Some(job)) = (satellites, &station.active_job) {
+                self.publish_satellite(station.id, job);
+            }
+
+            if self.stations.get(&station.id) == Some(&current) {
+                continue;
+            }
+            let published = self.publish(
+                &station_topic(&self.topics.status, station.id),
+                &json!({
+                    "name": station.name,
+                    "status": station.status,
+                    "observing": current.job.is_some(),
+                    "job": current.job,
+                }),
+                self.retain,
+            );
+            // try again with the next update
+            if published {
+                self.stations.insert(station.id, current);
+            }
+        }
+    }
+
+    fn publish_satellite(&self, station: u64, job: &JobInfo) {
+        self.publish(
+            &station_topic(&self.topics.satellite, station),
+            &json!({
+                "job": job.id,
+                "satellite": job.satellite,
+                "norad_cat_id": job.norad_cat_id,
+                "position": job.position,
+            }),
+            false,
+        );
+    }
+}
+
+fn station_topic(topic: &str, station: u64) -> String {
+    topic.replace("{station}", &station.to_string())
+}
+
+fn run(config: MqttConfig, rx: Receiver<(String, Vec<u8>, bool)>, connection: &Connection) {
+    let credentials = config
+        .credentials
+        .as_ref()
+        .map(|(username, password)| (username.as_str(), password.as_str()));
+    let ping_interval = Duration::new(u64::from(KEEP_ALIVE) / 2, 0);
+
+    loop {
+        let mut client = match MqttClient::connect(&config.address, &config.client_id, credentials)
+        {
+            Ok(client) => {
+                info!("Connected to MQTT broker at {}", config.address);
+                client
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to connect to MQTT broker {}: {}",
+                    config.address, err
+                );
+                thread::sleep(Duration::new(RECONNECT_INTERVAL, 0));
+                continue;
+            }
+        };
+
+        // drop what was queued for the previous connection, the current state
+        // is published again
+        rx.try_iter().for_each(drop);
+        connection.generation.fetch_add(1, Ordering::Relaxed);
+        connection.connected.store(true, Ordering::Relaxed);
+
+        let result = loop {
+            let result = match rx.recv_timeout(ping_interval) {
+                Ok((topic, payload, retain)) => client.publish(&topic, &payload, retain),
+                Err(RecvTimeoutError::Timeout) => client.ping(),
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+            if result.is_err() {
+                break result;
+            }
+        };
+
+        connection.connected.store(false, Ordering::Relaxed);
+        if let Err(err) = result {
+            warn!("Lost connection to MQTT broker {}: {}", config.address, err);
+        }
+        thread::sleep(Duration::new(RECONNECT_INTERVAL, 0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn read_packet(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).unwrap();
+        let mut packet = vec![0u8; header[1] as usize];
+        stream.read_exact(&mut packet).unwrap();
+
+        (header[0], packet)
+    }
+
+    fn publisher() -> (MqttPublisher, Receiver<(String, Vec<u8>, bool)>) {
+        let (tx, rx) = sync_channel(1);
+        let publisher = MqttPublisher {
+            connection: Arc::new(Connection::default()),
+            ended: Vec::new(),
+            generation: 0,
+            retain: true,
+            running: HashMap::new(),
+            stations: HashMap::new(),
+            topic_prefix: String::from("satnogs"),
+            topics: MqttTopics {
+                job: String::from("station/{station}/job"),
+                rotator: String::from("rotator"),
+                satellite: String::from("station/{station}/satellite"),
+                status: String::from("station/{station}/status"),
+                sysinfo: String::from("station/{station}/sysinfo"),
+            },
+            tx,
+        };
+
+        (publisher, rx)
+    }
+
+    #[test]
+    fn publishes_status_again_after_reconnect() {
+        let (mut publisher, rx) = publisher();
+        let state = crate::status::fixtures::state();
+
+        // nothing is queued without a connection
+        publisher.update(&state, true);
+        assert!(rx.try_recv().is_err());
+
+        publisher
+            .connection
+            .connected
+            .store(true, Ordering::Relaxed);
+        publisher.connection.generation.store(1, Ordering::Relaxed);
+        publisher.update(&state, true);
+        let (topic, _, retain) = rx.try_recv().unwrap();
+        assert_eq!(topic, "satnogs/station/7/status");
+        assert!(retain);
+
+        // unchanged
+        publisher.update(&state, true);
+        assert!(rx.try_recv().is_err());
+
+        // the queue is full, the status is published with a later update
+        publisher.connection.generation.store(2, Ordering::Relaxed);
+        publisher
+            .tx
+            .try_send((String::new(), vec![], false))
+            .unwrap();
+        publisher.update(&state, true);
+        rx.try_recv().unwrap();
+        publisher.update(&state, true);
+        assert_eq!(rx.try_recv().unwrap().0, "satnogs/station/7/status");
+    }
+
+    #[test]
+    fn publishes_job_end_after_reconnect() {
+        let (mut publisher, rx) = publisher();
+        let state = crate::status::fixtures::state();
+        publisher.running.insert(
+            7,
+            RunningJob {
+                id: 1,
+                info: json!({ "id": 1 }),
+            },
+        );
+
+        // the job ended while the broker couldn't be reached
+        publisher.update(&state, true);
+        assert!(rx.try_recv().is_err());
+
+        publisher
+            .connection
+            .connected
+            .store(true, Ordering::Relaxed);
+        publisher.update(&state, true);
+        let (topic, payload, _) = rx.try_recv().unwrap();
+        assert_eq!(topic, "satnogs/station/7/job");
+        assert_eq!(
+            serde_json::from_slice::<Value>(&payload).unwrap(),
+            json!({ "event": "end", "job": { "id": 1 } })
+        );
+        assert!(publisher.ended.is_empty());
+    }
+
+    #[test]
+    fn encodes_remaining_length() {
+        assert_eq!(encode(0xc0, &[]), vec![0xc0, 0x00]);
+        assert_eq!(&encode(0x30, &[0; 321])[..3], &[0x30, 0xc1, 0x02]);
+    }
+
+    #[test]
+    fn publishes_to_broker() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let broker = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (header, connect) = read_packet(&mut stream);
+            assert_eq!(header, 0x10);
+            assert_eq!(&connect[..7], b"\x00\x04MQTT\x04");
+            assert_eq!(&connect[10..], b"\x00\x07monitor");
+            stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+
+            let (header, publish) = read_packet(&mut stream);
+            let (header_ping, _) = read_packet(&mut stream);
+            stream.write_all(&[0xd0, 0x00]).unwrap();
+
+            (header, publish, header_ping)
+        });
+
+        let mut client = MqttClient::connect(&address, "monitor", None).unwrap();
+        client.publish("satnogs/rotator", b"{}", true).unwrap();
+        client.ping().unwrap();
+
+        let (header, publish, header_ping) = broker.join().unwrap();
+        assert_eq!(header, 0x31);
+        assert_eq!(&publish[..], b"\x00\x0fsatnogs/rotator{}");
+        assert_eq!(header_ping, 0xc0);
+    }
+}
@@ -0,0 +1,385 @@
+use crate::command;
+use crate::http;
+use crate::job::Job;
+use crate::state::State;
+use crate::station::Station;
+use crate::status::{JobInfo, Status};
+
+use chrono::{Duration, Utc};
+use log::{debug, info, warn};
+use satnogs_network_client::StationStatus;
+use serde_json::{json, Value};
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread;
+
+/// Kinds of notifications which can be enabled with `notify_events`
+pub const EVENTS: &[&str] = &["upcoming", "start", "end", "offline", "rotator"];
+
+pub struct NotifyConfig {
+    /// Seconds before the start of a job the upcoming notification is sent
+    pub before: i64,
+    pub command: Option<String>,
+    pub events: Vec<String>,
+    /// Seconds the command may run
+    pub timeout: u64,
+    pub webhook: Option<String>,
+}
+
+struct Notification {
+    event: &'static str,
+    message: String,
+    environment: Vec<(String, String)>,
+    payload: Value,
+}
+
+/// A running job, kept to notify about its end after it left the job list
+struct RunningJob {
+    id: u64,
+    environment: Vec<(String, String)>,
+    info: Value,
+}
+
+/// Sends notifications about upcoming and running jobs, offline stations and
+/// rotator errors to a webhook and/or runs a command for them. Notifications are
+/// delivered one after another by a background thread.
+pub struct Notifier {
+    before: Duration,
+    events: Vec<String>,
+    /// Jobs and the notifications already sent for them
+    notified: HashSet<(u64, &'static str)>,
+    offline: HashSet<u64>,
+    running: HashMap<u64, RunningJob>,
+    tx: SyncSender<Notification>,
+}
+
+impl Notifier {
+    pub fn new(config: NotifyConfig) -> Self {
+        for event in &config.events {
+            if !EVENTS.contains(&event.as_str()) {
+                warn!("Unknown notification event {:?}", event);
+            }
+        }
+
+        let (tx, rx) = sync_channel(100);
+        let before = Duration::seconds(config.before);
+        let events = config.events.clone();
+
+        thread::spawn(move || deliver(&config, rx));
+
+        Notifier {
+            before,
+            events,
+            notified: HashSet::new(),
+            offline: HashSet::new(),
+            running: HashMap::new(),
+            tx,
+        }
+    }
+
+    fn notify(
+        &self,
+        event: &'static str,
+        station: &Station,
+        message: String,
+        mut environment: Vec<(String, String)>,
+        job: Option<Value>,
+    ) {
+        if !self.events.iter().any(|enabled| enabled == event) {
+            return;
+        }
+
+        if environment.is_empty() {
+            environment.push((String::from("SATNOGS_STATION_ID"), station.id().to_string()));
+        }
+        environment.push((String::from("SATNOGS_EVENT"), event.to_string()));
+        environment.push((String::from("SATNOGS_MESSAGE"), message.clone()));
+
+        let notification = Notification {
+            event,
+            payload: json!({
+                "event": event,
+                "station": { "id": station.id(), "name": station.name() },
+                "message": message,
+                "job": job,
+            }),
+            message,
+            environment,
+        };
+        // never block the ui, a stuck command drops the following notifications
+        if self.tx.try_send(notification).is_err() {
+            warn!("Notification queue full, dropped {} notification", event);
+        }
+    }
+
+    /// Checks the state for changes worth a notification, `rotator_error` is the
+    /// deviation of the rotator in degrees considered an error
+    pub fn update(&mut self, state: &State, rotator_error: f64) {
+        let now = Utc::now();
+
+        for station in state.stations.values() {
+            let id = station.id();
+
+            if let StationStatus::Offline = station.info.status {
+                if self.offline.insert(id) {
+                    self.notify(
+                        "offline",
+                        station,
+                        format!("Station {} went offline", station.name()),
+                        vec![],
+                        None,
+                    );
+                }
+            } else {
+                self.offline.remove(&id);
+            }
+
+            for job in station.jobs.iter().filter(|job| job.start() > now) {
+                if job.start() - now <= self.before && self.notified.insert((job.id(), "upcoming"))
+                {
+                    self.notify(
+                        "upcoming",
+                        station,
+                        format!(
+                            "{} pass of {} starts at {}",
+                            station.name(),
+                            job.vessel_name(),
+                            job.start().format("%H:%M:%S UTC")
+                        ),
+                        command::job_environment(id, job),
+                        Some(job_info(job)),
+                    );
+                }
+            }
+
+            let active = station.active_job(now);
+            let previous = self.running.get(&id).map(|running| running.id);
+            if previous == active.map(Job::id) {
+                continue;
+            }
+
+            if let Some(running) = self.running.remove(&id) {
+                self.notify(
+                    "end",
+                    station,
+                    format!("{} finished job {}", station.name(), running.id),
+                    running.environment,
+                    Some(running.info),
+                );
+            }
+            if let Some(job) = active {
+                let running = RunningJob {
+                    id: job.id(),
+                    environment: command::job_environment(id, job),
+                    info: job_info(job),
+                };
+                self.notify(
+                    "start",
+                    station,
+                    format!("{} started observing {}", station.name(), job.vessel_name()),
+                    running.environment.clone(),
+                    Some(running.info.clone()),
+                );
+                self.running.insert(id, running);
+            }
+        }
+
+        self.check_rotator(state, rotator_error);
+
+        // forget the jobs which finished
+        let jobs = state
+            .stations
+            .values()
+            .flat_map(|station| station.jobs.iter().map(Job::id))
+            .collect::<HashSet<_>>();
+        self.notified.retain(|(job, _)| jobs.contains(job));
+    }
+
+    /// Notifies once per job if the rotator doesn't follow the satellite of the
+    /// active station while it's above the horizon
+    fn check_rotator(&mut self, state: &State, rotator_error: f64) {
+        let rotator = match Status::new(state).rotator {
+            Some(rotator) => rotator,
+            None => return,
+        };
+        let station = match state.stations.get(&state.active_station) {
+            Some(station) => station,
+            None => return,
+        };
+        let job = match station.jobs.first() {
+            Some(job) if self.running.get(&station.id()).map(|job| job.id) == Some(job.id()) => job,
+            _ => return,
+        };
+        if job.sat().el_deg <= 0.0 {
+            return;
+        }
+
+        let error = rotator
+            .azimuth_error
+            .unwrap_or_default()
+            .abs()
+            .max(rotator.elevation_error.unwrap_or_default().abs());
+        if error >= rotator_error && self.notified.insert((job.id(), "rotator")) {
+            self.notify(
+                "rotator",
+                station,
+                format!(
+                    "Rotator of {} is {:.1}° off {}",
+                    station.name(),
+                    error,
+                    job.vessel_name()
+                ),
+                command::job_environment(station.id(), job),
+                Some(job_info(job)),
+            );
+        }
+    }
+}
+
+fn job_info(job: &Job) -> Value {
+    serde_json::to_value(JobInfo::new(job)).unwrap_or_default()
+}
+
+fn deliver(config: &NotifyConfig, rx: Receiver<Notification>) {
+    let timeout = std::time::Duration::new(config.timeout, 0);
+
+    while let Ok(notification) = rx.recv() {
+        info!("{}", notification.message);
+
+        if let Some(url) = &config.webhook {
+            match http::post(url, "application/json", &notification.payload.to_string()) {
+                Ok(_) => debug!("Posted {} notification to {}", notification.event, url),
+                Err(err) => warn!("Failed to post notification to {}: {:#}", url, err),
+            }
+        }
+
+        if let Some(command) = &config.command {
+            command::run(
+                "notify_command",
+                command,
+                &notification.environment,
+                timeout,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::fixtures::{job, state};
+
+    fn notifier() -> (Notifier, Receiver<Notification>) {
+        let (tx, rx) = sync_channel(100);
+        let notifier = Notifier {
+            before: Duration::seconds(600),
+            events: EVENTS.iter().map(|event| event.to_string()).collect(),
+            notified: HashSet::new(),
+            offline: HashSet::new(),
+            running: HashMap::new(),
+            tx,
+        };
+
+        (notifier, rx)
+    }
+
+    fn events(rx: &Receiver<Notification>) -> Vec<&'static str> {
+        rx.try_iter()
+            .map(|notification| notification.event)
+            .collect()
+    }
+
+    #[test]
+    fn notifies_about_upcoming_jobs_once() {
+        let (mut notifier, rx) = notifier();
+        let mut state = state();
+        let now = Utc::now();
+        state.update_jobs(
+            7,
+            vec![
+                job(1, now + Duration::minutes(5), now + Duration::minutes(10)),
+                job(2, now + Duration::hours(2), now + Duration::hours(3)),
+            ],
+        );
+
+        notifier.update(&state, 15.0);
+        assert_eq!(events(&rx), vec!["upcoming"]);
+        notifier.update(&state, 15.0);
+        assert!(events(&rx).is_empty());
+    }
+
+    #[test]
+    fn notifies_about_start_and_end_of_jobs() {
+        let (mut notifier, rx) = notifier();
+        let mut state = state();
+        let now = Utc::now();
+        state.update_jobs(
+            7,
+            vec![job(
+                1,
+                now - Duration::minutes(1),
+                now + Duration::minutes(5),
+            )],
+        );
+
+        notifier.update(&state, 15.0);
+        assert_eq!(events(&rx), vec!["start"]);
+        notifier.update(&state, 15.0);
+        assert!(events(&rx).is_empty());
+
+        // the job left the job list
+        state.get_active_station_mut().jobs.clear();
+        notifier.update(&state, 15.0);
+        assert_eq!(events(&rx), vec!["end"]);
+        notifier.update(&state, 15.0);
+        assert!(events(&rx).is_empty());
+    }
+
+    #[test]
+    fn notifies_about_rotator_errors_across_north() {
+        let (mut notifier, rx) = notifier();
+        let mut state = state();
+        let now = Utc::now();
+        state.update_jobs(
+            7,
+            vec![job(
+                1,
+                now - Duration::minutes(1),
+                now + Duration::minutes(5),
+            )],
+        );
+        let sat = state.get_active_station_mut().jobs[0].vessel.sat_mut();
+        sat.az_deg = 1.0;
+        sat.el_deg = 30.0;
+
+        // 359° is only 2° off a satellite at 1°
+        state.rotator_position = Some((359.0, 30.0));
+        notifier.update(&state, 15.0);
+        assert_eq!(events(&rx), vec!["start"]);
+
+        state.rotator_position = Some((200.0, 30.0));
+        notifier.update(&state, 15.0);
+        notifier.update(&state, 15.0);
+        assert_eq!(events(&rx), vec!["rotator"]);
+    }
+
+    #[test]
+    fn notifies_once_per_offline_transition() {
+        let (mut notifier, rx) = notifier();
+        let mut state = state();
+
+        state.get_active_station_mut().info.status = StationStatus::Offline;
+        notifier.update(&state, 15.0);
+        notifier.update(&state, 15.0);
+        assert_eq!(events(&rx), vec!["offline"]);
+
+        state.get_active_station_mut().info.status = StationStatus::Online;
+        notifier.update(&state, 15.0);
+        assert!(events(&rx).is_empty());
+
+        state.get_active_station_mut().info.status = StationStatus::Offline;
+        notifier.update(&state, 15.0);
+        assert_eq!(events(&rx), vec!["offline"]);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::event::Event;
+use crate::Result;
+
+use lazy_static::lazy_static;
+use log::warn;
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc::SyncSender;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Seconds between two scans of the data path
+const SCAN_INTERVAL: u64 = 2;
+
+lazy_static! {
+    static ref RE: Regex = Regex::new(r".*/.*receiving_(satnogs|iq|waterfall)_(\d+)_.*").unwrap();
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum RecordingKind {
+    Audio,
+    Iq,
+    Waterfall,
+}
+
+impl RecordingKind {
+    pub fn name(&self) -> &str {
+        match self {
+            RecordingKind::Audio => "Audio",
+            RecordingKind::Iq => "IQ",
+            RecordingKind::Waterfall => "Waterfall",
+        }
+    }
+}
+
+/// A file the satnogs-client is writing during an observation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Recording {
+    pub kind: RecordingKind,
+    pub observation: u64,
+    /// File size in bytes
+    pub size: u64,
+    /// Growth in bytes per second since the last scan
+    pub rate: f64,
+}
+
+/// Scans the satnogs-client data paths for recordings and reports how fast they grow
+pub struct RecordingWatcher {
+    event_tx: SyncSender<Event>,
+    last_scan: Option<Instant>,
+    paths: Vec<PathBuf>,
+    sizes: HashMap<PathBuf, u64>,
+    /// Data paths which couldn't be read, e.g. before the satnogs-client created them
+    unreadable: HashSet<PathBuf>,
+}
+
+impl RecordingWatcher {
+    pub fn new(paths: &[&str], event_tx: SyncSender<Event>) -> Self {
+        RecordingWatcher {
+            event_tx,
+            last_scan: None,
+            paths: paths.iter().map(PathBuf::from).collect(),
+            sizes: HashMap::new(),
+            unreadable: HashSet::new(),
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        // the first scan only collects the file sizes, the growth rates are unknown
+        self.scan()?;
+
+        loop {
+            thread::sleep(Duration::new(SCAN_INTERVAL, 0));
+            let recordings = self.scan()?;
+            self.event_tx.send(Event::Recordings(recordings))?;
+        }
+    }
+
+    fn scan(&mut self) -> Result<Vec<Recording>> {
+        let now = Instant::now();
+        let elapsed = self
+            .last_scan
+            .map(|last_scan| now.duration_since(last_scan).as_secs_f64())
+            .unwrap_or(0.0);
+        self.last_scan = Some(now);
+
+        let mut sizes = HashMap::new();
+        let mut recordings = vec![];
+
+        let mut entries = vec![];
+        for path in &self.paths {
+            match std::fs::read_dir(path) {
+                Ok(dir) => {
+                    self.unreadable.remove(path);
+                    entries.push(dir);
+                }
+                Err(err) => {
+                    if self.unreadable.insert(path.clone()) {
+                        warn!("Failed to scan {} for recordings: {}", path.display(), err);
+                    }
+                }
+            }
+        }
+
+        for entry in entries.into_iter().flatten() {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(_) => continue,
+            };
+            let captures = match RE.captures(path.to_str().unwrap_or("")) {
+                Some(captures) => captures,
+                None => continue,
+            };
+
+            let kind = match &captures[1] {
+                "satnogs" => RecordingKind::Audio,
+                "iq" => RecordingKind::Iq,
+                _ => RecordingKind::Waterfall,
+            };
+            let observation = match captures[2].parse() {
+                Ok(observation) => observation,
+                Err(_) => continue,
+            };
+            // the file might have been moved by the satnogs-client in the meantime
+            let size = match path.metadata() {
+                Ok(metadata) => metadata.len(),
+                Err(_) => continue,
+            };
+
+            // files we haven't seen before have been created since the last scan
+            let rate = match (self.sizes.get(&path), elapsed > 0.0) {
+                (Some(last_size), true) => size.saturating_sub(*last_size) as f64 / elapsed,
+                (None, true) => size as f64 / elapsed,
+                _ => 0.0,
+            };
+
+            sizes.insert(path, size);
+            recordings.push(Recording {
+                kind,
+                observation,
+                size,
+                rate,
+            });
+        }
+
+        recordings.sort_by_key(|recording| (recording.observation, recording.kind as u8));
+        self.sizes = sizes;
+
+        Ok(recordings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::sync_channel;
+
+    #[test]
+    fn reports_kind_and_growth_of_recordings() {
+        let dir = std::env::temp_dir().join(format!("satnogs-growth-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let audio = dir.join("receiving_satnogs_1234_2020-03-23T09-34-47.ogg");
+        std::fs::write(&audio, vec![0; 100]).unwrap();
+        std::fs::write(dir.join("receiving_iq_1234_2020-03-23T09-34-47.raw"), b"iq").unwrap();
+        std::fs::write(
+            dir.join("receiving_waterfall_1234_2020-03-23T09-34-47.dat"),
+            b"",
+        )
+        .unwrap();
+        std::fs::write(dir.join("satnogs_1234_2020-03-23T09-34-47.ogg"), b"done").unwrap();
+
+        let (tx, _rx) = sync_channel(1);
+        let mut watcher = RecordingWatcher::new(&[dir.to_str().unwrap()], tx);
+        let recordings = watcher.scan().unwrap();
+        let kinds = recordings
+            .iter()
+            .map(|recording| (recording.kind, recording.observation, recording.rate))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (RecordingKind::Audio, 1234, 0.0),
+                (RecordingKind::Iq, 1234, 0.0),
+                (RecordingKind::Waterfall, 1234, 0.0),
+            ]
+        );
+
+        // pretend the last scan was two seconds ago
+        std::fs::write(&audio, vec![0; 500]).unwrap();
+        watcher.last_scan = Instant::now().checked_sub(Duration::new(2, 0));
+        let recordings = watcher.scan().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(recordings[0].size, 500);
+        assert!(
+            (recordings[0].rate - 200.0).abs() < 5.0,
+            "{}",
+            recordings[0].rate
+        );
+        assert_eq!(recordings[1].rate, 0.0);
+    }
+
+    #[test]
+    fn skips_missing_data_paths() {
+        let dir = std::env::temp_dir().join(format!("satnogs-recordings-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("receiving_satnogs_1234_2020-03-23T09-34-47.ogg"),
+            b"ogg",
+        )
+        .unwrap();
+
+        let missing = dir.join("missing");
+        let (tx, _rx) = sync_channel(1);
+        let mut watcher =
+            RecordingWatcher::new(&[missing.to_str().unwrap(), dir.to_str().unwrap()], tx);
+        let recordings = watcher.scan().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].observation, 1234);
+        assert!(watcher.unreadable.contains(&missing));
+    }
+}
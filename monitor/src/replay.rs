@@ -0,0 +1,287 @@
+use crate::event::Event;
+use crate::recordings::Recording;
+use crate::rigctld_client::RigStatus;
+use crate::satnogs::Data;
+use crate::sysinfo::SysInfo;
+use crate::Result;
+
+use anyhow::{bail, Context};
+use chrono::{DateTime, TimeZone, Utc};
+use log::Level;
+use satnogs_network_client as snc;
+use serde_derive::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::Path;
+use std::sync::mpsc::SyncSender;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// First line of a recording, everything needed to set up the ui without the network
+#[derive(Deserialize, Serialize)]
+struct Header<S> {
+    /// Start of the recording in milliseconds since the unix epoch
+    started: i64,
+    stations: Vec<S>,
+}
+
+/// An event and the milliseconds since the start of the recording it was received at
+#[derive(Deserialize, Serialize)]
+struct Entry<E> {
+    t: u64,
+    event: E,
+}
+
+/// The events which are recorded. Input, resize and tick events are generated by
+/// the ui itself and aren't part of a recording.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum RecordedEvent {
+    Jobs(u64, Vec<(snc::Job, snc::Observation)>),
+    Log(String, String),
+    Recordings(Vec<Recording>),
+    RigStatus(RigStatus),
+    RotatorPosition(f64, f64),
+    StationInfo(snc::StationInfo),
+    SystemInfo(Vec<u64>, SysInfo),
+    WaterfallCreated(u64, Option<u64>, f32, Vec<f32>),
+    WaterfallData(u64, i64, Vec<f32>),
+    WaterfallClosed(u64),
+}
+
+impl TryFrom<Event> for RecordedEvent {
+    type Error = Event;
+
+    fn try_from(event: Event) -> std::result::Result<Self, Event> {
+        Ok(match event {
+            Event::CommandResponse(Data::Jobs(station, jobs)) => RecordedEvent::Jobs(station, jobs),
+            Event::Log((level, message)) => RecordedEvent::Log(level.to_string(), message),
+            Event::Recordings(recordings) => RecordedEvent::Recordings(recordings),
+            Event::RigStatus(status) => RecordedEvent::RigStatus(status),
+            Event::RotatorPosition(azimuth, elevation) => {
+                RecordedEvent::RotatorPosition(azimuth, elevation)
+            }
+            Event::CommandResponse(Data::StationInfo(info)) => RecordedEvent::StationInfo(info),
+            Event::SystemInfo(stations, sys_info) => RecordedEvent::SystemInfo(stations, sys_info),
+            Event::WaterfallCreated(obs, station, center_freq, frequencies) => {
+                RecordedEvent::WaterfallCreated(obs, station, center_freq, frequencies)
+            }
+            Event::WaterfallData(obs, seconds, data) => {
+                RecordedEvent::WaterfallData(obs, seconds, data)
+            }
+            Event::WaterfallClosed(obs) => RecordedEvent::WaterfallClosed(obs),
+            event => return Err(event),
+        })
+    }
+}
+
+impl From<RecordedEvent> for Event {
+    fn from(event: RecordedEvent) -> Self {
+        match event {
+            RecordedEvent::Jobs(station, jobs) => Event::CommandResponse(Data::Jobs(station, jobs)),
+            RecordedEvent::Log(level, message) => {
+                Event::Log((level.parse().unwrap_or(Level::Info), message))
+            }
+            RecordedEvent::Recordings(recordings) => Event::Recordings(recordings),
+            RecordedEvent::RigStatus(status) => Event::RigStatus(status),
+            RecordedEvent::RotatorPosition(azimuth, elevation) => {
+                Event::RotatorPosition(azimuth, elevation)
+            }
+            RecordedEvent::StationInfo(info) => Event::CommandResponse(Data::StationInfo(info)),
+            RecordedEvent::SystemInfo(stations, sys_info) => Event::SystemInfo(stations, sys_info),
+            RecordedEvent::WaterfallCreated(obs, station, center_freq, frequencies) => {
+                Event::WaterfallCreated(obs, station, center_freq, frequencies)
+            }
+            RecordedEvent::WaterfallData(obs, seconds, data) => {
+                Event::WaterfallData(obs, seconds, data)
+            }
+            RecordedEvent::WaterfallClosed(obs) => Event::WaterfallClosed(obs),
+        }
+    }
+}
+
+/// Writes the events received by the ui as JSON lines
+pub struct Recorder {
+    started: Instant,
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, stations: &[&snc::StationInfo]) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("couldn't create recording {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        let header = Header {
+            started: Utc::now().timestamp_millis(),
+            stations: stations.to_vec(),
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+
+        Ok(Recorder {
+            started: Instant::now(),
+            writer,
+        })
+    }
+
+    pub fn record(&mut self, event: &RecordedEvent) -> Result<()> {
+        let entry = Entry {
+            t: self.started.elapsed().as_millis() as u64,
+            event,
+        };
+        serde_json::to_writer(&mut self.writer, &entry)?;
+        self.writer.write_all(b"\n")?;
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// Sends the events of a recording to the ui with their original timing
+pub struct Player {
+    entries: Lines<BufReader<File>>,
+    /// Start of the recording
+    started: DateTime<Utc>,
+    speed: f64,
+}
+
+impl Player {
+    /// Opens a recording and returns the stations it was recorded for
+    pub fn open(path: &Path, speed: f64) -> Result<(Self, Vec<snc::StationInfo>)> {
+        let file = File::open(path)
+            .with_context(|| format!("couldn't open recording {}", path.display()))?;
+        let mut entries = BufReader::new(file).lines();
+
+        let header: Header<snc::StationInfo> = match entries.next() {
+            Some(line) => serde_json::from_str(&line?)
+                .with_context(|| format!("invalid recording {}", path.display()))?,
+            None => bail!("empty recording {}", path.display()),
+        };
+
+        let started = Utc
+            .timestamp_millis_opt(header.started)
+            .single()
+            .unwrap_or_else(Utc::now);
+        let player = Player {
+            entries,
+            started,
+            speed,
+        };
+
+        Ok((player, header.stations))
+    }
+
+    /// Sends all events of the recording, blocks until the recording is finished or
+    /// the receiver is gone
+    pub fn run(self, tx: SyncSender<Event>) -> Result<()> {
+        let started = Instant::now();
+        let now = Utc::now();
+        let (recorded, speed) = (self.started, self.speed);
+        let shift = |time| replay_time(time, recorded, now, speed);
+
+        for (index, line) in self.entries.enumerate() {
+            let entry: Entry<RecordedEvent> = serde_json::from_str(&line?)
+                .with_context(|| format!("invalid event in line {}", index + 2))?;
+
+            let due = Duration::from_secs_f64(entry.t as f64 / 1000.0 / speed);
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                thread::sleep(wait);
+            }
+
+            let event = match entry.event {
+                // move the jobs into the present, they would be dropped as finished
+                // otherwise, and keep them in step with the other events
+                RecordedEvent::Jobs(station, mut jobs) => {
+                    for (job, observation) in &mut jobs {
+                        job.start = shift(job.start);
+                        job.end = shift(job.end);
+                        observation.start = shift(observation.start);
+                        observation.end = shift(observation.end);
+                    }
+                    RecordedEvent::Jobs(station, jobs)
+                }
+                event => event,
+            };
+
+            if tx.send(event.into()).is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Time of the replay started at `replayed` corresponding to a time of the
+/// recording started at `recorded`
+fn replay_time(
+    time: DateTime<Utc>,
+    recorded: DateTime<Utc>,
+    replayed: DateTime<Utc>,
+    speed: f64,
+) -> DateTime<Utc> {
+    let elapsed = (time - recorded).num_milliseconds() as f64 / speed;
+    replayed + chrono::Duration::milliseconds(elapsed as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::sync_channel;
+
+    #[test]
+    fn replays_recorded_events() {
+        let path =
+            std::env::temp_dir().join(format!("satnogs-monitor-{}.jsonl", std::process::id()));
+
+        let mut recorder = Recorder::create(&path, &[]).unwrap();
+        let mut record = |event| {
+            if let Ok(event) = RecordedEvent::try_from(event) {
+                recorder.record(&event).unwrap();
+            }
+        };
+        record(Event::RotatorPosition(180.0, 45.0));
+        record(Event::WaterfallData(1234, 10, vec![-80.0, -70.0]));
+        record(Event::Tick);
+        record(Event::WaterfallClosed(1234));
+        drop(recorder);
+
+        let (player, stations) = Player::open(&path, 1000.0).unwrap();
+        assert!(stations.is_empty());
+
+        let (tx, rx) = sync_channel(10);
+        player.run(tx).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let events: Vec<_> = rx.iter().collect();
+        assert_eq!(events.len(), 3);
+        assert!(
+            matches!(events[0], Event::RotatorPosition(azimuth, elevation) if azimuth == 180.0 && elevation == 45.0)
+        );
+        assert!(
+            matches!(&events[1], Event::WaterfallData(1234, 10, data) if data == &[-80.0, -70.0])
+        );
+        assert!(matches!(events[2], Event::WaterfallClosed(1234)));
+    }
+
+    #[test]
+    fn scales_job_times_with_the_speed() {
+        let recorded = Utc.with_ymd_and_hms(2020, 3, 23, 9, 0, 0).unwrap();
+        let replayed = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let start = Utc.with_ymd_and_hms(2020, 3, 23, 9, 20, 0).unwrap();
+
+        assert_eq!(
+            replay_time(start, recorded, replayed, 1.0),
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 20, 0).unwrap()
+        );
+        assert_eq!(
+            replay_time(start, recorded, replayed, 4.0),
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 5, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::Result;
+
+use anyhow::bail;
+use serde_derive::{Deserialize, Serialize};
+use std::io::Write;
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+
+/// Tuning state of the radio as reported by rigctld
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RigStatus {
+    /// VFO frequency in Hz
+    pub frequency: f64,
+    /// Modulation mode (FM, USB, ...)
+    pub mode: String,
+    /// Passband width in Hz
+    pub passband: i64,
+    /// Signal strength in dB relative to S9, not every rig supports this
+    pub strength: Option<i32>,
+}
+
+pub struct RigCtldClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl RigCtldClient {
+    pub fn new(address: &str) -> Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(std::time::Duration::new(1, 0)))?;
+        let reader = BufReader::new(stream.try_clone()?);
+
+        Ok(RigCtldClient {
+            reader,
+            writer: stream,
+        })
+    }
+
+    pub fn status(&mut self) -> Result<RigStatus> {
+        let frequency = self.frequency()?;
+        let (mode, passband) = self.mode()?;
+        // a rig without a signal strength meter answers with an error code
+        let strength = self.strength().ok();
+
+        Ok(RigStatus {
+            frequency,
+            mode,
+            passband,
+            strength,
+        })
+    }
+
+    pub fn frequency(&mut self) -> Result<f64> {
+        writeln!(&self.writer, "f")?;
+
+        let frequency = self.read_value()?;
+        Ok(frequency.parse()?)
+    }
+
+    pub fn mode(&mut self) -> Result<(String, i64)> {
+        writeln!(&self.writer, "m")?;
+
+        let mode = self.read_value()?;
+        let passband = self.read_value()?;
+
+        Ok((mode, passband.parse()?))
+    }
+
+    pub fn strength(&mut self) -> Result<i32> {
+        writeln!(&self.writer, "l STRENGTH")?;
+
+        let strength = self.read_value()?;
+        Ok(strength.parse()?)
+    }
+
+    fn read_value(&mut self) -> Result<String> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+
+        let line = line.trim();
+        if line.starts_with("RPRT") {
+            bail!("rigctld returned {}", line);
+        }
+
+        Ok(line.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Answers rigctld commands like a rig without a signal strength meter
+    fn spawn_rigctld() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            for line in BufReader::new(stream).lines() {
+                let response = match line.unwrap().trim() {
+                    "f" => "437525000\n",
+                    "m" => "FM\n15000\n",
+                    _ => "RPRT -11\n",
+                };
+                writer.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        address
+    }
+
+    #[test]
+    fn can_read_rig_status() {
+        let mut client = RigCtldClient::new(&spawn_rigctld()).unwrap();
+        let status = client.status().unwrap();
+
+        assert_eq!(
+            status,
+            RigStatus {
+                frequency: 437_525_000.0,
+                mode: "FM".to_string(),
+                passband: 15000,
+                strength: None,
+            }
+        );
+    }
+
+    #[test]
+    fn err_on_rigctld_error_report() {
+        let mut client = RigCtldClient::new(&spawn_rigctld()).unwrap();
+        assert!(client.strength().is_err());
+    }
+}
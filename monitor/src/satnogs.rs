@@ -1,16 +1,18 @@
 use crate::event::Event;
 use chrono::Utc;
 use log::{debug, error, trace, warn};
-use satnogs_network_client::{Job, Observation, ObservationFilter};
+use satnogs_network_client::{Job, Observation, ObservationFilter, StationInfo};
 use std::sync::mpsc::{sync_channel, SendError, SyncSender};
 use std::thread;
 
 pub enum Data {
     Jobs(u64, Vec<(Job, Observation)>),
+    StationInfo(StationInfo),
 }
 
 pub enum Command {
     GetJobs(u64),
+    GetStationInfo(u64),
 }
 
 pub struct Connection {
@@ -66,6 +68,14 @@ impl Connection {
                             error!("Failed to get observations for station {}", id);
                         }
                     }
+                    Command::GetStationInfo(id) => match client.station_info(id) {
+                        Ok(info) => data_tx
+                            .send(Event::CommandResponse(Data::StationInfo(info)))
+                            .unwrap_or_else(|e| {
+                                error!("Failed to send Data::StationInfo response: {}", e)
+                            }),
+                        Err(e) => error!("Failed to get info of station {}: {}", id, e),
+                    },
                 }
             }
 
@@ -2,11 +2,15 @@ use config::{Config, ConfigError, File};
 use directories::ProjectDirs;
 use serde_derive::Deserialize;
 
+use crate::widgets::{BinReduction, ColorMode, Colormap, MapCenter, MapMode, MapProjection};
+
 #[derive(Debug, Default, Deserialize)]
 pub struct StationConfig {
     #[serde(default)]
     pub local: bool,
     pub satnogs_id: u64,
+    /// satnogs-client data path of this station, overrides the global data path
+    pub data_path: Option<String>,
     pub rt_ip: Option<String>,
     pub rt_port: Option<u32>,
 }
@@ -16,6 +20,7 @@ impl StationConfig {
         StationConfig {
             local: false,
             satnogs_id: id,
+            data_path: None,
             rt_ip: None,
             rt_port: None,
         }
@@ -24,9 +29,26 @@ impl StationConfig {
 
 #[derive(Debug, Deserialize)]
 pub struct UiConfig {
+    pub auto_range: bool,
+    pub auto_range_high: f32,
+    pub auto_range_low: f32,
+    pub average_trace: bool,
+    pub average_weight: f32,
+    pub bin_reduction: BinReduction,
+    pub color_mode: ColorMode,
+    pub colormap: Colormap,
     pub db_min: f32,
     pub db_max: f32,
     pub ground_track_num: u8,
+    pub map_center: MapCenter,
+    /// Hours ahead the upcoming jobs are shown on the map
+    pub map_hours: u32,
+    pub map_mode: MapMode,
+    pub map_projection: MapProjection,
+    pub map_zoom: f64,
+    pub peak_hold_trace: bool,
+    pub rig_warn: f64,
+    pub rig_error: f64,
     pub rotator_warn: f64,
     pub rotator_error: f64,
     pub sat_footprint: bool,
@@ -37,13 +59,45 @@ pub struct UiConfig {
 #[derive(Debug, Deserialize)]
 pub struct Settings {
     pub api_endpoint: String,
+    pub export_path: Option<String>,
+    pub hook_aos: Option<String>,
+    pub hook_job_end: Option<String>,
+    pub hook_job_start: Option<String>,
+    pub hook_los: Option<String>,
+    pub hook_tca: Option<String>,
+    pub hook_timeout: u64,
+    pub http_address: Option<String>,
     pub job_update_interval: u64,
     pub log_level: Option<u64>,
+    pub metrics_address: Option<String>,
+    pub mqtt_address: Option<String>,
+    pub mqtt_client_id: String,
+    pub mqtt_password: Option<String>,
+    pub mqtt_retain: bool,
+    pub mqtt_topic_job: String,
+    pub mqtt_topic_prefix: String,
+    pub mqtt_topic_rotator: String,
+    pub mqtt_topic_satellite: String,
+    pub mqtt_topic_status: String,
+    pub mqtt_topic_sysinfo: String,
+    pub mqtt_username: Option<String>,
+    pub notify_before: i64,
+    pub notify_command: Option<String>,
+    pub notify_events: Vec<String>,
+    pub notify_timeout: u64,
+    pub notify_webhook: Option<String>,
     pub ui: UiConfig,
+    pub rigctld_address: Option<String>,
+    pub rigctld_interval: u64,
     pub rotctld_address: Option<String>,
     pub rotctld_interval: u64,
+    pub signal_search_width: f32,
+    pub signal_snr_threshold: f32,
+    pub status_file: Option<String>,
+    pub status_interval: u64,
     pub stations: Vec<StationConfig>,
     pub data_path: Option<String>,
+    pub waterfall_max_rows: usize,
     pub waterfall_zoom: f32,
 }
 
@@ -51,18 +105,54 @@ impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
         let mut settings = Config::new();
         settings.set_default("api_endpoint", "https://network.satnogs.org/api/")?;
+        settings.set_default("hook_timeout", 60)?;
         settings.set_default("job_update_interval", 600)?;
         settings.set_default("log_level", 0)?;
+        settings.set_default("mqtt_client_id", "satnogs-monitor")?;
+        settings.set_default("mqtt_retain", true)?;
+        settings.set_default("mqtt_topic_job", "station/{station}/job")?;
+        settings.set_default("mqtt_topic_prefix", "satnogs-monitor")?;
+        settings.set_default("mqtt_topic_rotator", "rotator")?;
+        settings.set_default("mqtt_topic_satellite", "station/{station}/satellite")?;
+        settings.set_default("mqtt_topic_status", "station/{station}/status")?;
+        settings.set_default("mqtt_topic_sysinfo", "station/{station}/sysinfo")?;
+        settings.set_default("notify_before", 600)?;
+        settings.set_default(
+            "notify_events",
+            vec!["upcoming", "start", "end", "offline", "rotator"],
+        )?;
+        settings.set_default("notify_timeout", 30)?;
+        settings.set_default("ui.auto_range", false)?;
+        settings.set_default("ui.auto_range_high", 99.5)?;
+        settings.set_default("ui.auto_range_low", 10.0)?;
+        settings.set_default("ui.average_trace", false)?;
+        settings.set_default("ui.average_weight", 0.1)?;
+        settings.set_default("ui.bin_reduction", "max")?;
+        settings.set_default("ui.color_mode", "auto")?;
+        settings.set_default("ui.colormap", "viridis")?;
         settings.set_default("ui.db_min", -100.0)?;
         settings.set_default("ui.db_max", 0)?;
         settings.set_default("ui.ground_track_num", 3)?;
+        settings.set_default("ui.map_center", "world")?;
+        settings.set_default("ui.map_hours", 6)?;
+        settings.set_default("ui.map_mode", "next")?;
+        settings.set_default("ui.map_projection", "equirectangular")?;
+        settings.set_default("ui.map_zoom", 1.0)?;
+        settings.set_default("ui.peak_hold_trace", false)?;
+        settings.set_default("ui.rig_warn", 500.0)?;
+        settings.set_default("ui.rig_error", 2000.0)?;
         settings.set_default("ui.rotator_warn", 5.0)?;
         settings.set_default("ui.rotator_error", 15.0)?;
         settings.set_default("ui.sat_footprint", true)?;
         settings.set_default("ui.spectrum_plot", false)?;
         settings.set_default("ui.waterfall", false)?;
+        settings.set_default("rigctld_interval", 5)?;
         settings.set_default("rotctld_interval", 5)?;
+        settings.set_default("signal_search_width", 10_000.0)?;
+        settings.set_default("signal_snr_threshold", 6.0)?;
+        settings.set_default("status_interval", 10)?;
         settings.set_default("stations", Vec::<config::Value>::new())?;
+        settings.set_default("waterfall_max_rows", 1024)?;
         settings.set_default("waterfall_zoom", 1.0)?;
 
         if let Some(project_dirs) = ProjectDirs::from("org", "SatNOGS", "satnogs-monitor") {
@@ -0,0 +1,287 @@
+use serde_derive::Serialize;
+use std::fmt;
+
+/// Number of recent rows the auto range is computed from
+const AUTO_RANGE_ROWS: usize = 20;
+
+/// Smallest dB range returned by `auto_range`
+const MIN_RANGE: f32 = 1.0;
+
+/// Rows above the SNR threshold needed before a signal counts as seen, a single
+/// row can be an interferer passing by
+const MIN_SIGNAL_ROWS: usize = 3;
+
+/// Finds the dB range of the most recent rows. The lower bound is the `low`
+/// percentile of the power values which tracks the noise floor, the upper bound
+/// the `high` percentile which tracks the peaks.
+pub fn auto_range(rows: &[(i64, Vec<f32>)], low: f32, high: f32) -> Option<[f32; 2]> {
+    let mut values = rows
+        .iter()
+        .rev()
+        .take(AUTO_RANGE_ROWS)
+        .flat_map(|(_timestamp, row)| row.iter().cloned())
+        .filter(|value| value.is_finite())
+        .collect::<Vec<_>>();
+
+    if values.is_empty() {
+        return None;
+    }
+
+    let floor = percentile(&mut values, low);
+    let peak = percentile(&mut values, high);
+
+    Some([floor, peak.max(floor + MIN_RANGE)])
+}
+
+/// Estimates the noise floor of a row by the median power of its bins, most bins
+/// of a satellite pass contain nothing but noise
+pub fn noise_floor(row: &[f32]) -> Option<f32> {
+    let mut values = row
+        .iter()
+        .cloned()
+        .filter(|value| value.is_finite())
+        .collect::<Vec<_>>();
+
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(percentile(&mut values, 50.0))
+}
+
+/// Strongest bin of a row relative to the noise floor of the row
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Peak {
+    /// Frequency offset of the bin in Hz
+    pub frequency: f32,
+    pub snr: f32,
+}
+
+/// Finds the strongest bin within `width` Hz around the frequency offset `center`
+pub fn find_peak(frequencies: &[f32], row: &[f32], center: f32, width: f32) -> Option<Peak> {
+    let floor = noise_floor(row)?;
+
+    frequencies
+        .iter()
+        .zip(row)
+        .filter(|(frequency, power)| {
+            (**frequency - center).abs() <= width / 2.0 && power.is_finite()
+        })
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(frequency, power)| Peak {
+            frequency: *frequency,
+            snr: power - floor,
+        })
+}
+
+/// Signal statistics of the waterfall rows of an observation
+#[derive(Clone, Debug, Serialize)]
+pub struct SignalReport {
+    pub observation: u64,
+    /// Rows analyzed so far
+    pub rows: usize,
+    /// Rows with a peak above the SNR threshold
+    pub detections: usize,
+    /// Noise floor of the latest row
+    pub noise_floor: Option<f32>,
+    /// Peak of the latest row
+    pub last_peak: Option<Peak>,
+    /// Strongest peak of the observation
+    pub peak: Option<Peak>,
+}
+
+impl SignalReport {
+    pub fn new(observation: u64) -> Self {
+        SignalReport {
+            observation,
+            rows: 0,
+            detections: 0,
+            noise_floor: None,
+            last_peak: None,
+            peak: None,
+        }
+    }
+
+    /// Searches the row for a signal `width` Hz around the expected frequency
+    /// offset `center`
+    pub fn update(
+        &mut self,
+        frequencies: &[f32],
+        row: &[f32],
+        center: f32,
+        width: f32,
+        threshold: f32,
+    ) {
+        self.rows += 1;
+        self.noise_floor = noise_floor(row);
+        self.last_peak = find_peak(frequencies, row, center, width);
+
+        if let Some(peak) = self.last_peak {
+            if peak.snr >= threshold {
+                self.detections += 1;
+            }
+
+            let strongest = match self.peak {
+                Some(best) => peak.snr > best.snr,
+                None => true,
+            };
+            if strongest {
+                self.peak = Some(peak);
+            }
+        }
+    }
+
+    /// Whether enough rows contained a signal above the threshold
+    pub fn seen(&self) -> bool {
+        self.detections >= MIN_SIGNAL_ROWS
+    }
+}
+
+impl fmt::Display for SignalReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} in {} of {} rows",
+            if self.seen() {
+                "signal seen"
+            } else {
+                "no signal seen"
+            },
+            self.detections,
+            self.rows
+        )?;
+
+        if let Some(peak) = self.peak {
+            write!(
+                f,
+                ", peak SNR {:.1} dB at {:+.1} kHz",
+                peak.snr,
+                peak.frequency / 1000.0
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Exponential moving average and peak hold of the spectrum rows
+#[derive(Clone, Debug, Default)]
+pub struct SpectrumTraces {
+    pub average: Vec<f32>,
+    pub peak_hold: Vec<f32>,
+}
+
+impl SpectrumTraces {
+    pub fn from_rows(rows: &[(i64, Vec<f32>)], weight: f32) -> Self {
+        let mut traces = SpectrumTraces::default();
+        for (_timestamp, row) in rows {
+            traces.update(row, weight);
+        }
+
+        traces
+    }
+
+    /// Adds a row to the traces, `weight` (0 - 1) is the weight of the row in the
+    /// average
+    pub fn update(&mut self, row: &[f32], weight: f32) {
+        // start over if the FFT size changed
+        if self.average.len() != row.len() {
+            self.average = row.to_vec();
+            self.peak_hold = row.to_vec();
+            return;
+        }
+
+        let weight = weight.clamp(0.0, 1.0);
+        for ((average, peak), power) in self
+            .average
+            .iter_mut()
+            .zip(self.peak_hold.iter_mut())
+            .zip(row)
+        {
+            if !power.is_finite() {
+                continue;
+            }
+
+            *average = if average.is_finite() {
+                *average + weight * (power - *average)
+            } else {
+                *power
+            };
+            *peak = peak.max(*power);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.average.clear();
+        self.peak_hold.clear();
+    }
+}
+
+/// Returns the `percentile` (0 - 100) of `values`, the values get reordered
+fn percentile(values: &mut [f32], percentile: f32) -> f32 {
+    let rank = (percentile.clamp(0.0, 100.0) / 100.0 * (values.len() - 1) as f32).round();
+    let (_, value, _) = values.select_nth_unstable_by(rank as usize, |a, b| a.total_cmp(b));
+
+    *value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_range_tracks_noise_floor_and_peak() {
+        let mut row = vec![-90.0; 98];
+        row.extend_from_slice(&[-20.0, -10.0]);
+        let rows = vec![(0, row)];
+
+        assert_eq!(auto_range(&rows, 10.0, 100.0), Some([-90.0, -10.0]));
+        assert_eq!(auto_range(&rows, 10.0, 50.0), Some([-90.0, -89.0]));
+        assert_eq!(auto_range(&[], 10.0, 90.0), None);
+    }
+
+    #[test]
+    fn detects_signal_near_expected_frequency() {
+        let frequencies = (0..100)
+            .map(|bin| (bin - 50) as f32 * 100.0)
+            .collect::<Vec<_>>();
+        let mut row = vec![-90.0; 100];
+        row[10] = -40.0;
+        row[55] = -70.0;
+
+        let peak = find_peak(&frequencies, &row, 0.0, 2000.0).unwrap();
+        assert_eq!(peak.frequency, 500.0);
+        assert_eq!(peak.snr, 20.0);
+
+        let mut report = SignalReport::new(1234);
+        for _ in 0..MIN_SIGNAL_ROWS {
+            assert!(!report.seen());
+            report.update(&frequencies, &row, 0.0, 2000.0, 6.0);
+        }
+        assert!(report.seen());
+
+        report.update(&frequencies, &vec![-90.0; 100], 0.0, 2000.0, 6.0);
+        assert_eq!(report.rows, MIN_SIGNAL_ROWS + 1);
+        assert_eq!(report.last_peak.unwrap().snr, 0.0);
+        assert_eq!(report.peak, Some(peak));
+    }
+
+    #[test]
+    fn traces_average_and_hold_peaks() {
+        let rows = vec![
+            (0, vec![-90.0, -50.0]),
+            (1, vec![-80.0, -60.0]),
+            (2, vec![-80.0, -60.0]),
+        ];
+        let mut traces = SpectrumTraces::from_rows(&rows, 0.5);
+
+        assert_eq!(traces.average, vec![-82.5, -57.5]);
+        assert_eq!(traces.peak_hold, vec![-80.0, -50.0]);
+
+        traces.update(&[-80.0, -60.0, -70.0], 0.5);
+        assert_eq!(traces.average, vec![-80.0, -60.0, -70.0]);
+
+        traces.reset();
+        assert!(traces.average.is_empty());
+    }
+}
@@ -1,14 +1,27 @@
+use crate::job::Job;
+use crate::recordings::Recording;
+use crate::rigctld_client::RigStatus;
+use crate::spectrum::SignalReport;
 use crate::station::Station;
 use crate::vessel::Vessel;
 
+use chrono::{DateTime, Utc};
+use circular_queue::CircularQueue;
 use satnogs_network_client as snc;
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Number of finished observations kept in the signal history
+const SIGNAL_HISTORY_LEN: usize = 20;
 
 pub struct State {
     pub active_station: u64,
     pub stations: BTreeMap<u64, Station>,
+    pub recordings: Vec<Recording>,
+    pub rig_status: Option<RigStatus>,
     pub rotator_position: Option<(f64, f64)>,
+    /// Signal reports of finished observations, newest first
+    pub signal_history: CircularQueue<SignalReport>,
     pub vessels: HashMap<u64, Vessel>,
 }
 
@@ -16,7 +29,10 @@ impl State {
     pub fn new() -> Self {
         State {
             active_station: 0,
+            recordings: vec![],
+            rig_status: None,
             rotator_position: None,
+            signal_history: CircularQueue::with_capacity(SIGNAL_HISTORY_LEN),
             stations: BTreeMap::new(),
             vessels: HashMap::new(),
         }
@@ -47,13 +63,80 @@ impl State {
         }
     }
 
+    /// Updates the satellite position of the first and the running or next job of
+    /// every station
     pub fn update_vessel_position(&mut self, ground_tracks: u8) {
-        let station = self.get_active_station_mut();
-        if let Some(job) = station.jobs.iter_mut().next() {
-            job.update_position(ground_tracks);
+        let now = Utc::now();
+        for station in self.stations.values_mut() {
+            let mut jobs = station.jobs.iter_mut();
+            if let Some(job) = jobs.next() {
+                job.update_position(ground_tracks);
+                // finished jobs are only removed once a minute
+                if job.end() <= now {
+                    if let Some(next) = jobs.find(|job| job.end() > now) {
+                        next.update_position(ground_tracks);
+                    }
+                }
+            }
         }
     }
 
+    /// Updates the satellite positions of the jobs running or starting before
+    /// `until` at `station` or at all stations
+    pub fn update_upcoming_positions(
+        &mut self,
+        station: Option<u64>,
+        until: DateTime<Utc>,
+        ground_tracks: u8,
+    ) {
+        let now = Utc::now();
+        for station in self
+            .stations
+            .values_mut()
+            .filter(|other| station.is_none() || station == Some(other.id()))
+        {
+            for job in station
+                .jobs
+                .iter_mut()
+                .filter(|job| job.end() > now && job.start() <= until)
+            {
+                job.update_position(ground_tracks);
+            }
+        }
+    }
+
+    /// The jobs running or starting before `until` at `station` or at all
+    /// stations, one per satellite ordered by their start
+    pub fn upcoming_jobs(&self, station: Option<u64>, until: DateTime<Utc>) -> Vec<&Job> {
+        let now = Utc::now();
+        let mut jobs = self
+            .stations
+            .values()
+            .filter(|other| station.is_none() || station == Some(other.id()))
+            .flat_map(|station| station.jobs.iter())
+            .filter(|job| job.end() > now && job.start() <= until)
+            .collect::<Vec<_>>();
+        jobs.sort_by_key(|job| job.start());
+
+        let mut satellites = HashSet::new();
+        jobs.retain(|job| satellites.insert(job.observation.norad_cat_id));
+        jobs
+    }
+
+    /// Finds the job of an observation at any of the stations
+    pub fn job(&self, observation: u64) -> Option<&Job> {
+        self.stations
+            .values()
+            .flat_map(|station| station.jobs.iter())
+            .find(|job| job.id() == observation)
+    }
+
+    pub fn observation_recordings(&self, observation: u64) -> impl Iterator<Item = &Recording> {
+        self.recordings
+            .iter()
+            .filter(move |recording| recording.observation == observation)
+    }
+
     pub fn next_station(&mut self) {
         if self.stations.len() > 1 {
             self.active_station = *self
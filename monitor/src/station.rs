@@ -1,4 +1,4 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use satnogs_network_client as snc;
 use std::fmt;
 
@@ -28,6 +28,13 @@ impl Station {
         &self.info.name
     }
 
+    /// The job running at `now`
+    pub fn active_job(&self, now: DateTime<Utc>) -> Option<&Job> {
+        self.jobs
+            .iter()
+            .find(|job| job.start() <= now && now < job.end())
+    }
+
     pub fn remove_finished_jobs(&mut self) {
         self.jobs
             .retain(|job| job.end() - Utc::now() > Duration::zero());
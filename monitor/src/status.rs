@@ -0,0 +1,222 @@
+use crate::job::Job;
+use crate::rigctld_client::RigStatus;
+use crate::state::State;
+use crate::station::Station;
+use crate::sysinfo::SysInfo;
+
+use chrono::{DateTime, Utc};
+use satnogs_network_client::StationStatus;
+use serde_derive::Serialize;
+
+/// Machine readable snapshot of the monitored stations
+#[derive(Serialize)]
+pub struct Status<'a> {
+    pub time: DateTime<Utc>,
+    pub active_station: u64,
+    pub rig: Option<&'a RigStatus>,
+    pub rotator: Option<RotatorInfo>,
+    pub stations: Vec<StationInfo<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct StationInfo<'a> {
+    pub id: u64,
+    pub name: &'a str,
+    /// online, testing or offline
+    pub status: &'static str,
+    /// When the network heard from the station the last time
+    pub last_seen: Option<DateTime<Utc>>,
+    pub jobs: usize,
+    /// The job observing right now
+    pub active_job: Option<JobInfo<'a>>,
+    /// The first job which hasn't started yet
+    pub next_job: Option<JobInfo<'a>>,
+    pub sys_info: &'a SysInfo,
+}
+
+#[derive(Serialize)]
+pub struct JobInfo<'a> {
+    pub id: u64,
+    pub satellite: &'a str,
+    pub norad_cat_id: u64,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub mode: &'a str,
+    /// Transmitter frequency in Hz
+    pub frequency: f64,
+    /// Doppler shifted frequency in Hz
+    pub doppler_frequency: f64,
+    pub position: SatellitePosition,
+}
+
+/// Position of the satellite seen from the station
+#[derive(Serialize)]
+pub struct SatellitePosition {
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+    pub range_km: f64,
+    pub range_rate_km_s: f64,
+}
+
+/// Rotator position and its deviation from the satellite of the active station
+#[derive(Serialize)]
+pub struct RotatorInfo {
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub azimuth_error: Option<f64>,
+    pub elevation_error: Option<f64>,
+}
+
+impl<'a> Status<'a> {
+    pub fn new(state: &'a State) -> Self {
+        let now = Utc::now();
+        let target = state
+            .stations
+            .get(&state.active_station)
+            .and_then(|station| station.jobs.first())
+            .map(|job| job.sat());
+
+        Status {
+            time: now,
+            active_station: state.active_station,
+            rig: state.rig_status.as_ref(),
+            rotator: state
+                .rotator_position
+                .map(|(azimuth, elevation)| RotatorInfo {
+                    azimuth,
+                    elevation,
+                    azimuth_error: target.map(|sat| azimuth_difference(azimuth, sat.az_deg)),
+                    elevation_error: target.map(|sat| elevation - sat.el_deg),
+                }),
+            stations: state
+                .stations
+                .values()
+                .map(|station| StationInfo::new(station, now))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> StationInfo<'a> {
+    fn new(station: &'a Station, now: DateTime<Utc>) -> Self {
+        StationInfo {
+            id: station.id(),
+            name: station.name(),
+            status: match station.info.status {
+                StationStatus::Online => "online",
+                StationStatus::Offline => "offline",
+                StationStatus::Testing => "testing",
+            },
+            last_seen: station.info.last_seen,
+            jobs: station.jobs.len(),
+            active_job: station.active_job(now).map(JobInfo::new),
+            next_job: station
+                .jobs
+                .iter()
+                .find(|job| job.start() > now)
+                .map(JobInfo::new),
+            sys_info: &station.sys_info,
+        }
+    }
+}
+
+impl<'a> JobInfo<'a> {
+    pub fn new(job: &'a Job) -> Self {
+        let sat = job.sat();
+
+        JobInfo {
+            id: job.id(),
+            satellite: job.vessel_name(),
+            norad_cat_id: job.observation.norad_cat_id,
+            start: job.start(),
+            end: job.end(),
+            mode: job.mode(),
+            frequency: job.frequency_mhz() * 1_000_000.0,
+            doppler_frequency: job.doppler_frequency_mhz() * 1_000_000.0,
+            position: SatellitePosition {
+                azimuth: sat.az_deg,
+                elevation: sat.el_deg,
+                latitude: sat.lat_deg,
+                longitude: sat.lon_deg,
+                altitude_km: sat.alt_km,
+                range_km: sat.range_km,
+                range_rate_km_s: sat.range_rate_km_sec,
+            },
+        }
+    }
+}
+
+/// Difference of two azimuths in degrees in (-180, 180], the short way around
+fn azimuth_difference(azimuth: f64, target: f64) -> f64 {
+    let difference = (azimuth - target + 540.0).rem_euclid(360.0) - 180.0;
+    if difference == -180.0 {
+        180.0
+    } else {
+        difference
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn azimuth_difference_crosses_north() {
+        assert_eq!(azimuth_difference(359.0, 1.0), -2.0);
+        assert_eq!(azimuth_difference(1.0, 359.0), 2.0);
+        assert_eq!(azimuth_difference(90.0, 80.0), 10.0);
+        assert_eq!(azimuth_difference(0.0, 180.0), 180.0);
+        assert_eq!(azimuth_difference(180.0, 0.0), 180.0);
+    }
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use crate::state::State;
+    use crate::station::Station;
+    use chrono::{DateTime, Utc};
+    use satnogs_network_client as snc;
+    use serde_json::json;
+
+    /// A state with the active station 7 "Test" at 48°N 11°E.
+    pub fn state() -> State {
+        let info = serde_json::from_value(json!({
+            "id": 7, "name": "Test", "altitude": 500.0, "min_horizon": 0.0, "lat": 48.0,
+            "lng": 11.0, "qthlocator": "JN58", "antenna": [],
+            "created": "2020-01-01T00:00:00Z", "last_seen": null, "status": "Online",
+            "observations": 0, "description": "",
+        }))
+        .unwrap();
+        let mut state = State::new();
+        state.add_station(Station::new(info));
+        state.active_station = 7;
+
+        state
+    }
+
+    /// An ISS job and its observation on station 7.
+    pub fn job(id: u64, start: DateTime<Utc>, end: DateTime<Utc>) -> (snc::Job, snc::Observation) {
+        let job = serde_json::from_value(json!({
+            "id": id, "start": start, "end": end, "ground_station": 7,
+            "tle0": "ISS (ZARYA)",
+            "tle1": "1 25544U 98067A   20083.51939583  .00000869  00000-0  23932-4 0  9994",
+            "tle2": "2 25544  51.6438  29.6372 0004398  38.4867  93.3426 15.49540236219011",
+            "frequency": 145_800_000, "mode": "FM", "transmitter": "abc", "baud": null,
+        }))
+        .unwrap();
+        let observation = serde_json::from_value(json!({
+            "id": id, "start": start, "end": end, "ground_station": 7, "transmitter": "abc",
+            "norad_cat_id": 25544, "payload": null, "waterfall": null, "demoddata": [],
+            "station_name": "Test", "station_lat": 48.0, "station_lng": 11.0,
+            "station_alt": 500.0, "vetted_status": "unknown", "rise_azimuth": 0.0,
+            "set_azimuth": 180.0, "max_altitude": 45.0, "archived": false,
+            "archive_url": null, "client_version": "", "client_metadata": "",
+        }))
+        .unwrap();
+
+        (job, observation)
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+
+/// Equatorial radius of the earth in km
+const EARTH_RADIUS_KM: f64 = 6_378.135;
+
+/// Latitude and longitude in degrees of the point where the sun is in the zenith,
+/// accurate to about a hundredth of a degree
+pub fn subsolar_point(time: DateTime<Utc>) -> (f64, f64) {
+    // days since J2000.0
+    let days = (time.timestamp_millis() as f64 / 86_400_000.0) - 10_957.5;
+
+    let mean_longitude = 280.460 + 0.985_647_4 * days;
+    let mean_anomaly = (357.528 + 0.985_600_3 * days).to_radians();
+    let ecliptic_longitude =
+        (mean_longitude + 1.915 * mean_anomaly.sin() + 0.020 * (2.0 * mean_anomaly).sin())
+            .to_radians();
+    let obliquity = (23.439 - 0.000_000_4 * days).to_radians();
+
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin())
+        .atan2(ecliptic_longitude.cos())
+        .to_degrees();
+    let declination = (obliquity.sin() * ecliptic_longitude.sin())
+        .asin()
+        .to_degrees();
+    let sidereal_time = 280.460_618_37 + 360.985_647_366_29 * days;
+
+    (declination, normalize(right_ascension - sidereal_time))
+}
+
+/// Longitudes and latitudes of the day/night terminator, the great circle 90°
+/// away from the subsolar point
+pub fn terminator(time: DateTime<Utc>) -> Vec<(f64, f64)> {
+    let (lat, lon) = subsolar_point(time);
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+
+    (0..720)
+        .map(|step| {
+            let bearing = (f64::from(step) / 2.0).to_radians();
+            // the point a quarter of a great circle away in direction `bearing`
+            let point_lat = (lat.cos() * bearing.cos()).asin();
+            let point_lon = lon + (bearing.sin() * lat.cos()).atan2(-lat.sin() * point_lat.sin());
+            (normalize(point_lon.to_degrees()), point_lat.to_degrees())
+        })
+        .collect()
+}
+
+/// Whether a satellite at the given latitude, longitude and altitude is in the
+/// shadow of the earth. The shadow is modeled as a cylinder, the sun is far
+/// enough away for its rays to be parallel.
+pub fn is_eclipsed(lat_deg: f64, lon_deg: f64, alt_km: f64, time: DateTime<Utc>) -> bool {
+    let sun = unit_vector(subsolar_point(time));
+    let (x, y, z) = unit_vector((lat_deg, lon_deg));
+    let radius = EARTH_RADIUS_KM + alt_km;
+    let satellite = (x * radius, y * radius, z * radius);
+
+    let towards_sun = satellite.0 * sun.0 + satellite.1 * sun.1 + satellite.2 * sun.2;
+    if towards_sun >= 0.0 {
+        return false;
+    }
+    // distance from the line through the center of the earth towards the sun
+    let distance_squared = radius * radius - towards_sun * towards_sun;
+    distance_squared < EARTH_RADIUS_KM * EARTH_RADIUS_KM
+}
+
+fn unit_vector((lat, lon): (f64, f64)) -> (f64, f64, f64) {
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    (lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin())
+}
+
+/// Wraps a longitude into -180° to 180°
+fn normalize(lon: f64) -> f64 {
+    let lon = lon.rem_euclid(360.0);
+    if lon >= 180.0 {
+        lon - 360.0
+    } else {
+        lon
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn finds_the_subsolar_point() {
+        // the sun is over the tropic of cancer at the june solstice and close
+        // to the prime meridian around noon UTC
+        let (lat, lon) = subsolar_point(Utc.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap());
+        assert!((lat - 23.44).abs() < 0.05, "{}", lat);
+        assert!(lon.abs() < 1.0, "{}", lon);
+
+        let (lat, lon) = subsolar_point(Utc.with_ymd_and_hms(2024, 3, 20, 0, 0, 0).unwrap());
+        assert!(lat.abs() < 0.5, "{}", lat);
+        assert!((lon.abs() - 180.0).abs() < 3.0, "{}", lon);
+    }
+
+    #[test]
+    fn terminator_is_a_quarter_circle_away() {
+        let time = Utc.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap();
+        let (lat, lon) = subsolar_point(time);
+        let sun = unit_vector((lat, lon));
+
+        for point in terminator(time) {
+            let (x, y, z) = unit_vector((point.1, point.0));
+            assert!((x * sun.0 + y * sun.1 + z * sun.2).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn detects_the_shadow_of_the_earth() {
+        let time = Utc.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap();
+        let (lat, lon) = subsolar_point(time);
+
+        assert!(!is_eclipsed(lat, lon, 500.0, time));
+        assert!(is_eclipsed(-lat, normalize(lon + 180.0), 500.0, time));
+        // above the terminator the satellite still sees the sun
+        assert!(!is_eclipsed(lat - 90.0, lon, 500.0, time));
+    }
+}
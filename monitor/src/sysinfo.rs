@@ -1,9 +1,18 @@
-use systemstat::data::{CPULoad, Duration, Memory};
+use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
 
-#[derive(Clone, Default)]
+/// Memory of the station host in bytes
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct MemInfo {
+    pub total: u64,
+    pub free: u64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct SysInfo {
     pub cpu_temp: Option<f32>,
-    pub cpu_load: Option<Vec<CPULoad>>,
-    pub mem: Option<Memory>,
+    /// Idle share of each CPU core from 0.0 to 1.0
+    pub cpu_idle: Option<Vec<f32>>,
+    pub mem: Option<MemInfo>,
     pub uptime: Option<Duration>,
 }
@@ -2,8 +2,8 @@ use anyhow::bail;
 use anyhow::Context as _;
 use chrono::prelude::*;
 use circular_queue::CircularQueue;
-use log::{debug, trace};
-use satnogs_network_client::{Client, StationStatus};
+use log::{debug, error, info, trace, warn};
+use satnogs_network_client::StationStatus;
 use signal_hook::consts::signal::SIGWINCH;
 use signal_hook::iterator::Signals;
 use termion::input::{MouseTerminal, TermRead};
@@ -19,54 +19,177 @@ use tui::Terminal;
 
 use tui::widgets::{Axis, Chart, Dataset, Marker};
 
+use std::collections::{BTreeMap, HashSet};
+use std::convert::TryFrom;
 use std::f64::consts;
-use std::io;
+use std::io::{self, Write};
+use std::path::Path;
 use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender};
 use std::thread;
 
+use crate::api::{self, Api};
 use crate::event::Event;
+use crate::export;
+use crate::hooks::Hooks;
 use crate::job::Job;
+use crate::metrics::Metrics;
+use crate::mqtt::MqttPublisher;
+use crate::notifications::Notifier;
+use crate::recordings::Recording;
+use crate::replay::{RecordedEvent, Recorder};
+use crate::rigctld_client::RigStatus;
 use crate::satnogs;
 use crate::settings::Settings;
+use crate::spectrum::{self, SignalReport, SpectrumTraces};
 use crate::state::State;
 use crate::station::Station;
-use crate::widgets::{InfoBar, Waterfall, WaterfallLegend};
+use crate::status::Status;
+use crate::sun;
+use crate::waterfall::WaterfallBuffer;
+use crate::widgets::{
+    wrap_longitude, BinReduction, ColorMode, Colormap, FrequencyViewport, InfoBar, MapCenter,
+    MapMode, MapProjection, MapViewport, Projected, Waterfall, WaterfallLegend,
+};
 
 use crate::Result;
 
 const COL_LIGHT_BG: Color = Color::DarkGray;
 const COL_DARK_CYAN: Color = Color::DarkGray;
 const COL_WHITE: Color = Color::White;
+/// Color of the night side of the terminator and the tracks in the earth's shadow
+const COL_ECLIPSE: Color = Color::Blue;
+
+/// Colors of the satellites of the upcoming jobs on the map, the next job is red
+const SATELLITE_COLORS: [Color; 6] = [
+    Color::LightYellow,
+    Color::LightMagenta,
+    Color::LightGreen,
+    Color::LightBlue,
+    Color::Yellow,
+    Color::Magenta,
+];
+
+/// Rows scrolled by `PgUp` and `PgDn` in the waterfall
+const WATERFALL_SCROLL_ROWS: usize = 20;
+
+/// Seconds the satnogs-client gets after the start of an observation to start recording
+const RECORDING_GRACE_PERIOD: i64 = 30;
+
+/// How the spectrum plot and the waterfall display the FFT rows
+pub struct SpectrumView {
+    /// Plot the moving average of the rows
+    pub average_trace: bool,
+    pub color_mode: ColorMode,
+    pub colormap: Colormap,
+    pub db_range: [f32; 2],
+    /// Frequency offset marked on the waterfall
+    pub marker: Option<f32>,
+    /// Plot the maximum of the rows
+    pub peak_hold_trace: bool,
+    pub reduction: BinReduction,
+    pub viewport: FrequencyViewport,
+}
+
+/// What the map shows
+pub struct MapView {
+    pub footprint: bool,
+    pub ground_tracks: usize,
+    pub center: MapCenter,
+    pub mode: MapMode,
+    /// Pan steps away from the center
+    pub pan: (i32, i32),
+    pub projection: MapProjection,
+    /// Jobs starting later aren't shown
+    pub until: DateTime<Utc>,
+    pub zoom: f64,
+}
+
+impl MapView {
+    /// The station whose jobs are shown, `None` for all stations
+    fn station(&self, active: u64) -> Option<u64> {
+        match self.mode {
+            MapMode::All => None,
+            _ => Some(active),
+        }
+    }
+}
+
+/// Waterfall of a running observation and what is derived from its rows
+struct LiveWaterfall {
+    auto_range: Option<[f32; 2]>,
+    center_freq: f32,
+    data: WaterfallBuffer,
+    frequencies: Vec<f32>,
+    /// Number of recent rows hidden while scrolling back, 0 shows the live waterfall
+    scroll: usize,
+    signal: SignalReport,
+    /// Station writing the waterfall if it is known from the data path
+    station: Option<u64>,
+    traces: SpectrumTraces,
+}
 
-type LogQueue = CircularQueue<(DateTime<Utc>, log::Level, String)>;
-type TermBackend = TermionBackend<MouseTerminal<RawTerminal<io::Stdout>>>;
+impl LiveWaterfall {
+    /// Rows up to the scroll position
+    fn rows(&self) -> &[(i64, Vec<f32>)] {
+        let rows = self.data.rows();
+        &rows[..rows.len() - self.scroll]
+    }
+
+    fn scroll_older(&mut self, rows: usize) {
+        self.scroll = self
+            .scroll
+            .saturating_add(rows)
+            .min(self.data.len().saturating_sub(1));
+    }
+
+    fn scroll_newer(&mut self, rows: usize) {
+        self.scroll = self.scroll.saturating_sub(rows);
+    }
+}
+
+pub type LogQueue = CircularQueue<(DateTime<Utc>, log::Level, String)>;
+pub type TermBackend = TermionBackend<MouseTerminal<RawTerminal<io::Stdout>>>;
 
 pub struct Ui {
+    api: Option<Api>,
     events: Receiver<Event>,
+    hooks: Option<Hooks>,
     logs: LogQueue,
     last_job_update: std::time::Instant,
-    network: satnogs::Connection,
+    /// Pan steps of the map away from its center
+    map_pan: (i32, i32),
+    metrics: Option<Metrics>,
+    mqtt: Option<MqttPublisher>,
+    /// Connection to the SatNOGS network, not used while replaying a recording
+    network: Option<satnogs::Connection>,
+    notifier: Option<Notifier>,
+    recorder: Option<Recorder>,
     sender: SyncSender<Event>,
     settings: Settings,
     show_logs: bool,
+    show_signal_history: bool,
     shutdown: bool,
     size: Rect,
+    stalled_observations: HashSet<u64>,
     state: State,
-    terminal: Terminal<TermBackend>,
+    /// Receives the status as JSON lines in headless mode
+    status_output: Option<Box<dyn Write>>,
+    /// The terminal the ui is drawn to, there is none in headless mode
+    terminal: Option<Terminal<TermBackend>>,
     ticks: u32,
-    waterfall_data: Vec<(i64, Vec<f32>)>,
-    waterfall_frequencies: Vec<f32>,
-    waterfall_obs_id: u64,
+    viewport: FrequencyViewport,
+    /// Waterfalls of the running observations by observation id
+    waterfalls: BTreeMap<u64, LiveWaterfall>,
 }
 
 impl Ui {
-    pub fn new(settings: Settings, _client: Client, state: State) -> Result<Self> {
+    pub fn new(settings: Settings, state: State) -> Result<Self> {
         let (sender, reciever) = sync_channel(100);
 
         // Must be called before any threads are launched
         let winch_send = sender.clone();
-        let mut signals = Signals::new(&[SIGWINCH])
-            .context("couldn't register resize signal handler")?;
+        let mut signals =
+            Signals::new(&[SIGWINCH]).context("couldn't register resize signal handler")?;
         thread::spawn(move || {
             for _ in signals.forever() {
                 let _ = winch_send.send(Event::Resize);
@@ -82,6 +205,30 @@ impl Ui {
             }
         });
 
+        let terminal = init_terminal(115, 32)?;
+
+        Ok(Self::with_terminal(
+            settings,
+            state,
+            (sender, reciever),
+            Some(terminal),
+        ))
+    }
+
+    /// Runs without a terminal and writes the status of the stations as JSON lines
+    /// to `output` every `status_interval` seconds
+    pub fn headless(settings: Settings, state: State, output: Box<dyn Write>) -> Self {
+        let mut ui = Self::with_terminal(settings, state, sync_channel(100), None);
+        ui.status_output = Some(output);
+        ui
+    }
+
+    fn with_terminal(
+        settings: Settings,
+        state: State,
+        (sender, reciever): (SyncSender<Event>, Receiver<Event>),
+        terminal: Option<Terminal<TermBackend>>,
+    ) -> Self {
         let tick_send = sender.clone();
         thread::spawn(move || {
             while tick_send.send(Event::Tick).is_ok() {
@@ -89,47 +236,147 @@ impl Ui {
             }
         });
 
-        let stdout = io::stdout()
-            .into_raw_mode()
-            .context("failed to put stdout into raw mode")?;
-        let stdout = MouseTerminal::from(stdout);
-        let backend = TermionBackend::new(stdout);
-        let mut terminal = Terminal::new(backend).context("failed to create terminal")?;
-
-        // Fail gracefully, when Terminal size is below 115 x 32
-        let size = terminal.size().context("failed to get terminal size")?;
-        if size.width < 115 || size.height < 32 {
-            bail!("Terminal size is under 115 columns or 32 lines");
-        }
-
-        terminal.clear().context("failed to clear terminal")?;
-        terminal.hide_cursor().context("failed to hide cursor")?;
+        let viewport = FrequencyViewport::new(settings.waterfall_zoom);
 
-        let ui = Self {
+        Self {
+            api: None,
             events: reciever,
+            hooks: None,
             last_job_update: std::time::Instant::now(),
             logs: CircularQueue::with_capacity(100),
-            network: satnogs::Connection::new(sender.clone(), settings.api_endpoint.clone()),
+            map_pan: (0, 0),
+            metrics: None,
+            mqtt: None,
+            network: Some(satnogs::Connection::new(
+                sender.clone(),
+                settings.api_endpoint.clone(),
+            )),
+            notifier: None,
+            recorder: None,
             sender,
             settings,
             show_logs: false,
+            show_signal_history: false,
             shutdown: false,
             size: Rect::default(),
+            stalled_observations: HashSet::new(),
             state,
+            status_output: None,
             terminal,
             ticks: 0,
-            waterfall_obs_id: 0,
-            waterfall_frequencies: vec![],
-            waterfall_data: vec![],
-        };
+            viewport,
+            waterfalls: BTreeMap::new(),
+        }
+    }
+
+    /// Stops requesting jobs from the network, they are part of the replayed events
+    pub fn offline(mut self) -> Self {
+        self.network = None;
+        self
+    }
+
+    /// Records all events except user input to replay them later
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
 
-        Ok(ui)
+    /// Keeps the metrics served on the metrics endpoint up to date
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Forwards the state changes to the event stream of the http API
+    pub fn with_api(mut self, api: Api) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    /// Publishes the station state to a MQTT broker
+    pub fn with_mqtt(mut self, mqtt: MqttPublisher) -> Self {
+        self.mqtt = Some(mqtt);
+        self
+    }
+
+    /// Notifies about upcoming passes and problems of the stations
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Runs the configured commands around the passes
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = Some(hooks);
+        self
     }
 
     pub fn sender(&self) -> SyncSender<Event> {
         self.sender.clone()
     }
 
+    /// Derives the dB range of all waterfalls from their recent rows
+    fn update_auto_ranges(&mut self) {
+        let ui = &self.settings.ui;
+        for live in self.waterfalls.values_mut() {
+            live.auto_range =
+                spectrum::auto_range(live.data.rows(), ui.auto_range_low, ui.auto_range_high);
+        }
+    }
+
+    /// The configured dB range or the auto range of the recent waterfall rows
+    fn db_range(&self) -> [f32; 2] {
+        let auto_range = self
+            .active_waterfall_id()
+            .and_then(|id| self.waterfalls[&id].auto_range);
+
+        match (self.settings.ui.auto_range, auto_range) {
+            (true, Some(auto_range)) => auto_range,
+            _ => [self.settings.ui.db_min, self.settings.ui.db_max],
+        }
+    }
+
+    /// Offset of the doppler shifted downlink from the waterfall center frequency
+    fn expected_offset(&self, observation: u64) -> Option<f32> {
+        let waterfall = self.waterfalls.get(&observation)?;
+
+        self.state
+            .job(observation)
+            .map(|job| (job.doppler_frequency_mhz() * 1_000_000.0) as f32 - waterfall.center_freq)
+    }
+
+    /// Observation of the most recent waterfall of the active station. Waterfalls
+    /// which can't be assigned to any station are shown if the active station has
+    /// none.
+    fn active_waterfall_id(&self) -> Option<u64> {
+        let station = self.state.get_active_station();
+
+        self.waterfalls
+            .iter()
+            .rev()
+            .find(|(id, waterfall)| match waterfall.station {
+                Some(station_id) => station_id == station.id(),
+                None => station.jobs.iter().any(|job| job.id() == **id),
+            })
+            .or_else(|| {
+                self.waterfalls.iter().rev().find(|(id, waterfall)| {
+                    waterfall.station.is_none() && self.state.job(**id).is_none()
+                })
+            })
+            .map(|(id, _)| *id)
+    }
+
+    fn active_waterfall_mut(&mut self) -> Option<&mut LiveWaterfall> {
+        let id = self.active_waterfall_id()?;
+        self.waterfalls.get_mut(&id)
+    }
+
+    fn pan(&mut self, steps: i32) {
+        if let Some(id) = self.active_waterfall_id() {
+            self.viewport.pan(&self.waterfalls[&id].frequencies, steps);
+        }
+    }
+
     fn next_station(&mut self) {
         self.state.next_station();
     }
@@ -139,14 +386,14 @@ impl Ui {
     }
 
     fn draw(&mut self) -> Result<()> {
-        let size = self
-            .terminal
-            .size()
-            .context("Failed to get terminal size")?;
+        let terminal = match self.terminal.as_mut() {
+            Some(terminal) => terminal,
+            None => return Ok(()),
+        };
+
+        let size = terminal.size().context("Failed to get terminal size")?;
         if self.size != size {
-            self.terminal
-                .resize(size)
-                .context("Failed to resize terminal")?;
+            terminal.resize(size).context("Failed to resize terminal")?;
             self.size = size;
         }
 
@@ -161,30 +408,64 @@ impl Ui {
             .constraints([Constraint::Length(40), Constraint::Min(0)].as_ref())
             .split(rows[1]);
 
-        let log_area = Layout::default()
+        let bottom_areas = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(10)].as_ref())
-            .split(self.size)[1];
+            .constraints(
+                [
+                    Constraint::Min(0),
+                    Constraint::Length(10),
+                    Constraint::Length(10),
+                ]
+                .as_ref(),
+            )
+            .split(self.size);
+        let log_area = bottom_areas[2];
+        // the signal history is shown above the log window if both are enabled
+        let signal_history_area = if self.show_logs {
+            bottom_areas[1]
+        } else {
+            bottom_areas[2]
+        };
 
         let station = self.state.get_active_station();
 
         let logs = &self.logs;
         let show_logs = self.show_logs;
-        let ground_tracks = self.settings.ui.ground_track_num as usize;
-        let sat_footprint = self.settings.ui.sat_footprint;
+        let show_signal_history = self.show_signal_history;
+        let map_view = self.map_view();
         let spectrum_plot = self.settings.ui.spectrum_plot;
+        let snr_threshold = self.settings.signal_snr_threshold;
+        let rig_thresholds = (self.settings.ui.rig_warn, self.settings.ui.rig_error);
         let rot_thresholds = (
             self.settings.ui.rotator_warn,
             self.settings.ui.rotator_error,
         );
-        let db_range = [self.settings.ui.db_min, self.settings.ui.db_max];
+        let db_range = self.db_range();
         let state = &self.state;
+        let stalled_observations = &self.stalled_observations;
         let waterfall = self.settings.ui.waterfall;
-        let waterfall_data = &self.waterfall_data;
-        let waterfall_frequencies = &self.waterfall_frequencies;
-        let waterfall_zoom = self.settings.waterfall_zoom;
+        let waterfalls = &self.waterfalls;
+        let active_waterfall = self.active_waterfall_id();
+        let live_waterfall = active_waterfall
+            .and_then(|id| waterfalls.get(&id))
+            .filter(|live| !live.rows().is_empty() && !live.frequencies.is_empty());
+        let spectrum_view = SpectrumView {
+            average_trace: self.settings.ui.average_trace,
+            color_mode: self.settings.ui.color_mode,
+            colormap: self.settings.ui.colormap,
+            db_range,
+            marker: active_waterfall.and_then(|id| self.expected_offset(id)),
+            peak_hold_trace: self.settings.ui.peak_hold_trace,
+            reduction: self.settings.ui.bin_reduction,
+            viewport: self.viewport,
+        };
 
-        self.terminal
+        let terminal = match self.terminal.as_mut() {
+            Some(terminal) => terminal,
+            None => return Ok(()),
+        };
+
+        terminal
             .draw(|mut f| {
                 InfoBar::new(state)
                     .style(Style::default().fg(Color::White).bg(Color::DarkGray))
@@ -192,8 +473,22 @@ impl Ui {
                     .render(&mut f, rows[0]);
 
                 let mut rect = render_station_view(&mut f, body[0], &station);
-                rect = render_next_job_view(&mut f, rect, &station);
+                rect = render_next_job_view(
+                    &mut f,
+                    rect,
+                    &station,
+                    state.rig_status.as_ref(),
+                    rig_thresholds,
+                );
                 if let Some(job) = station.jobs.iter().next() {
+                    let recordings = state.observation_recordings(job.id()).collect::<Vec<_>>();
+                    let stalled = stalled_observations.contains(&job.id());
+                    if !recordings.is_empty() || stalled {
+                        rect = render_recordings_view(&mut f, rect, &recordings, stalled);
+                    }
+                    if let Some(live) = waterfalls.get(&job.id()) {
+                        rect = render_signal_view(&mut f, rect, &live.signal, snr_threshold);
+                    }
                     rect = render_polar_plot(&mut f, rect, &job);
                 }
                 rect = render_satellite_view(&mut f, rect, state, rot_thresholds);
@@ -210,7 +505,10 @@ impl Ui {
 
                 // render main area on the right
                 rect = body[1];
-                if !waterfall_data.is_empty() && !waterfall_frequencies.is_empty() {
+                if let Some(live) = live_waterfall {
+                    let waterfall_data = live.rows();
+                    let waterfall_frequencies = &live.frequencies;
+                    let traces = &live.traces;
                     let layout = Layout::default().direction(Direction::Vertical);
 
                     rect = match (spectrum_plot, waterfall) {
@@ -226,8 +524,8 @@ impl Ui {
                                 area[1],
                                 &waterfall_frequencies,
                                 &waterfall_data,
-                                db_range,
-                                waterfall_zoom,
+                                traces,
+                                &spectrum_view,
                             );
 
                             area[0]
@@ -244,7 +542,7 @@ impl Ui {
                                 area[1],
                                 &waterfall_frequencies,
                                 &waterfall_data,
-                                db_range,
+                                &spectrum_view,
                             );
 
                             area[0]
@@ -267,15 +565,15 @@ impl Ui {
                                 area[1],
                                 &waterfall_frequencies,
                                 &waterfall_data,
-                                db_range,
-                                waterfall_zoom,
+                                traces,
+                                &spectrum_view,
                             );
                             render_waterfall(
                                 &mut f,
                                 area[2],
                                 &waterfall_frequencies,
                                 &waterfall_data,
-                                db_range,
+                                &spectrum_view,
                             );
 
                             area[0]
@@ -284,7 +582,11 @@ impl Ui {
                     };
                 }
 
-                render_map_view(&mut f, rect, &station, ground_tracks, sat_footprint);
+                render_map_view(&mut f, rect, state, &station, &map_view);
+
+                if show_signal_history {
+                    render_signal_history_view(&mut f, signal_history_area, state);
+                }
 
                 if show_logs {
                     render_log_view(&mut f, log_area, logs);
@@ -302,20 +604,68 @@ impl Ui {
         match *event {
             Key(Ctrl('c')) => self.shutdown = true,
             Key(Char('f')) => self.settings.ui.sat_footprint = !self.settings.ui.sat_footprint,
+            Key(Char('h')) => self.show_signal_history = !self.show_signal_history,
+            Key(Char('s')) => {
+                self.settings.ui.map_mode = self.settings.ui.map_mode.next();
+                self.update_map_positions();
+            }
+            Key(Char('z')) => {
+                self.settings.ui.map_zoom = MapViewport::zoom_in(self.settings.ui.map_zoom);
+            }
+            Key(Char('Z')) => {
+                self.settings.ui.map_zoom = MapViewport::zoom_out(self.settings.ui.map_zoom);
+            }
+            Key(Char('H')) => self.map_pan.0 -= 1,
+            Key(Char('L')) => self.map_pan.0 += 1,
+            Key(Char('K')) => {
+                let max_pan = MapViewport::max_pan(self.settings.ui.map_zoom);
+                self.map_pan.1 = (self.map_pan.1 + 1).min(max_pan);
+            }
+            Key(Char('J')) => {
+                let max_pan = MapViewport::max_pan(self.settings.ui.map_zoom);
+                self.map_pan.1 = (self.map_pan.1 - 1).max(-max_pan);
+            }
+            Key(Char('g')) => {
+                self.settings.ui.map_center = self.settings.ui.map_center.next();
+                self.map_pan = (0, 0);
+            }
+            Key(Char('o')) => {
+                self.settings.ui.map_projection = self.settings.ui.map_projection.next();
+                self.map_pan = (0, 0);
+            }
             Key(Char('l')) => self.show_logs = !self.show_logs,
+            Key(Char('p')) => self.export_waterfall(),
             Key(Char('\t')) => self.next_station(),
             Key(Ctrl('\t')) => self.prev_station(),
             Key(Char('q')) => self.shutdown = true,
-            Key(Char('+')) => {
-                if self.settings.waterfall_zoom < 10.0 {
-                    self.settings.waterfall_zoom += 0.5;
+            Key(Char('+')) => self.viewport.zoom_in(),
+            Key(Char('-')) => self.viewport.zoom_out(),
+            Key(Left) => self.pan(-1),
+            Key(Right) => self.pan(1),
+            Key(Char('0')) => self.viewport.reset(),
+            Key(Char('m')) => {
+                self.settings.ui.bin_reduction = self.settings.ui.bin_reduction.next();
+            }
+            Key(Char('c')) => self.settings.ui.colormap = self.settings.ui.colormap.next(),
+            Key(Char('a')) => {
+                self.settings.ui.auto_range = !self.settings.ui.auto_range;
+                if self.settings.ui.auto_range {
+                    self.update_auto_ranges();
                 }
             }
-            Key(Char('-')) => {
-                if self.settings.waterfall_zoom > 1.0 {
-                    self.settings.waterfall_zoom -= 0.5;
+            Key(Char('e')) => self.settings.ui.average_trace = !self.settings.ui.average_trace,
+            Key(Char('x')) => {
+                self.settings.ui.peak_hold_trace = !self.settings.ui.peak_hold_trace;
+            }
+            Key(Char('r')) => {
+                if let Some(live) = self.active_waterfall_mut() {
+                    live.traces.reset();
                 }
             }
+            Key(PageDown) => self.scroll_waterfall_older(WATERFALL_SCROLL_ROWS),
+            Key(PageUp) => self.scroll_waterfall_newer(WATERFALL_SCROLL_ROWS),
+            Key(Home) => self.scroll_waterfall_older(usize::MAX),
+            Key(End) => self.scroll_waterfall_newer(usize::MAX),
             Key(key) => {
                 debug!("Key Event: {:?}", key);
             }
@@ -323,22 +673,128 @@ impl Ui {
         }
     }
 
+    fn map_view(&self) -> MapView {
+        MapView {
+            footprint: self.settings.ui.sat_footprint,
+            ground_tracks: self.settings.ui.ground_track_num as usize,
+            center: self.settings.ui.map_center,
+            mode: self.settings.ui.map_mode,
+            pan: self.map_pan,
+            projection: self.settings.ui.map_projection,
+            until: Utc::now() + chrono::Duration::hours(i64::from(self.settings.ui.map_hours)),
+            zoom: self.settings.ui.map_zoom,
+        }
+    }
+
+    /// Updates the satellite positions of the upcoming jobs shown on the map
+    fn update_map_positions(&mut self) {
+        let view = self.map_view();
+        if view.mode != MapMode::Next {
+            self.state.update_upcoming_positions(
+                view.station(self.state.active_station),
+                view.until,
+                self.settings.ui.ground_track_num,
+            );
+        }
+    }
+
+    fn scroll_waterfall_older(&mut self, rows: usize) {
+        if let Some(live) = self.active_waterfall_mut() {
+            live.scroll_older(rows);
+        }
+    }
+
+    fn scroll_waterfall_newer(&mut self, rows: usize) {
+        if let Some(live) = self.active_waterfall_mut() {
+            live.scroll_newer(rows);
+        }
+    }
+
+    fn export_waterfall(&self) {
+        let (obs_id, live) = match self
+            .active_waterfall_id()
+            .map(|id| (id, &self.waterfalls[&id]))
+        {
+            Some((id, live)) if !live.data.is_empty() && !live.frequencies.is_empty() => (id, live),
+            _ => {
+                warn!("No waterfall data to export");
+                return;
+            }
+        };
+
+        let path = Path::new(self.settings.export_path.as_deref().unwrap_or(".")).join(format!(
+            "waterfall_{}_{}.png",
+            obs_id,
+            Utc::now().format("%Y-%m-%dT%H-%M-%S")
+        ));
+
+        match export::waterfall_png(&path, &live.frequencies, live.data.rows(), self.db_range()) {
+            Ok(_) => info!("Exported waterfall to {}", path.display()),
+            Err(err) => error!("Failed to export waterfall: {:#}", err),
+        }
+    }
+
     fn handle_event(&mut self, event: Event) {
+        let event = match self.recorder.as_mut() {
+            Some(recorder) => match RecordedEvent::try_from(event) {
+                Ok(recorded) => {
+                    if let Err(err) = recorder.record(&recorded) {
+                        error!("Failed to record event, recording stopped: {:#}", err);
+                        self.recorder = None;
+                    }
+                    recorded.into()
+                }
+                Err(event) => event,
+            },
+            None => event,
+        };
+
+        if let Some(mqtt) = &self.mqtt {
+            mqtt.handle_event(&event);
+        }
+        if let Some(api) = &self.api {
+            api.handle_event(&event);
+        }
+
         match event {
+            Event::ApiRequest(path, reply) => {
+                let _ = reply.send(api::answer(&path, &self.state, &self.logs));
+            }
             Event::CommandResponse(data) => match data {
                 satnogs::Data::Jobs(station_id, jobs) => {
                     self.state.update_jobs(station_id, jobs);
                     self.state
                         .update_vessel_position(self.settings.ui.ground_track_num);
                 }
+                satnogs::Data::StationInfo(info) => {
+                    if let Some(station) = self.state.stations.get_mut(&info.id) {
+                        station.info = info;
+                    }
+                }
             },
+            Event::Recordings(recordings) => {
+                self.state.recordings = recordings;
+                self.check_recordings();
+            }
             Event::Resize => debug!("Terminal size changed"),
             Event::Input(event) => {
                 self.handle_input(&event);
             }
             Event::Log((level, message)) => {
+                // without a terminal the log goes to stderr, stdout carries the status
+                if self.terminal.is_none() {
+                    eprintln!(
+                        "{} {:<5} {}",
+                        Utc::now().format("%Y-%m-%d %H:%M:%S"),
+                        level,
+                        message
+                    );
+                }
                 self.logs.push((Utc::now(), level, message));
             }
+            Event::RigStatus(status) => {
+                self.state.rig_status = Some(status);
+            }
             Event::RotatorPosition(azimuth, elevation) => {
                 self.state.rotator_position = Some((azimuth, elevation));
             }
@@ -354,30 +810,139 @@ impl Ui {
             Event::Tick => {
                 self.handle_tick();
             }
-            Event::WaterfallCreated(obs_id, frequencies) => {
-                self.waterfall_obs_id = obs_id;
-                self.waterfall_frequencies = frequencies;
+            Event::WaterfallCreated(obs_id, station, center_freq, frequencies) => {
+                self.waterfalls.insert(
+                    obs_id,
+                    LiveWaterfall {
+                        auto_range: None,
+                        center_freq,
+                        data: WaterfallBuffer::new(self.settings.waterfall_max_rows),
+                        frequencies,
+                        scroll: 0,
+                        signal: SignalReport::new(obs_id),
+                        station,
+                        traces: SpectrumTraces::default(),
+                    },
+                );
+            }
+            Event::WaterfallData(obs_id, seconds, data) => {
+                if let Some(metrics) = self.metrics.as_mut() {
+                    metrics.waterfall_row();
+                }
+
+                let expected_offset = self.expected_offset(obs_id).unwrap_or(0.0);
+                let settings = &self.settings;
+                let live = match self.waterfalls.get_mut(&obs_id) {
+                    Some(live) => live,
+                    None => return,
+                };
+
+                live.traces.update(&data, settings.ui.average_weight);
+                live.signal.update(
+                    &live.frequencies,
+                    &data,
+                    expected_offset,
+                    settings.signal_search_width,
+                    settings.signal_snr_threshold,
+                );
+                live.data.push((seconds, data));
+                // keep the rows on screen while scrolled back
+                if live.scroll > 0 {
+                    live.scroll_older(1);
+                }
+                // the percentiles are expensive, only update them if they are used
+                if settings.ui.auto_range {
+                    live.auto_range = spectrum::auto_range(
+                        live.data.rows(),
+                        settings.ui.auto_range_low,
+                        settings.ui.auto_range_high,
+                    );
+                }
+            }
+            Event::WaterfallClosed(obs_id) => {
+                let report = match self.waterfalls.remove(&obs_id) {
+                    Some(live) if live.signal.rows > 0 => live.signal,
+                    _ => return,
+                };
+
+                if report.seen() {
+                    info!("Observation {}: {}", report.observation, report);
+                } else {
+                    warn!("Observation {}: {}", report.observation, report);
+                }
+                self.state.signal_history.push(report);
             }
-            Event::WaterfallData(seconds, data) => {
-                self.waterfall_data.push((seconds, data));
+        }
+    }
+
+    /// Warns once per observation if a local station is observing but none of its
+    /// recordings grow
+    fn check_recordings(&mut self) {
+        let now = Utc::now();
+        let grace_period = chrono::Duration::seconds(RECORDING_GRACE_PERIOD);
+
+        for station_config in self.settings.stations.iter().filter(|s| s.local) {
+            let job = match self
+                .state
+                .stations
+                .get(&station_config.satnogs_id)
+                .and_then(|station| station.jobs.first())
+            {
+                Some(job) => job,
+                None => continue,
+            };
+
+            if now < job.start() + grace_period || now > job.end() {
+                continue;
             }
-            Event::WaterfallClosed(_obs_id) => {
-                self.waterfall_data.clear();
-                self.waterfall_frequencies.clear();
-                self.waterfall_obs_id = 0;
+
+            let growing = self
+                .state
+                .observation_recordings(job.id())
+                .any(|recording| recording.rate > 0.0);
+
+            if growing {
+                self.stalled_observations.remove(&job.id());
+            } else if self.stalled_observations.insert(job.id()) {
+                warn!(
+                    "Observation {} is running but no recording is growing",
+                    job.id()
+                );
             }
         }
     }
 
     fn handle_tick(&mut self) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.flush() {
+                error!("Failed to write recording, recording stopped: {:#}", err);
+                self.recorder = None;
+            }
+        }
+
         if self.last_job_update.elapsed().as_secs() >= self.settings.job_update_interval {
             self.update_jobs();
         }
 
         self.ticks += 1;
-        if self.ticks % 5 == 0 {
+        let update_positions = self.ticks % 5 == 0;
+        if update_positions {
             self.state
                 .update_vessel_position(self.settings.ui.ground_track_num);
+            self.update_map_positions();
+        }
+
+        if let Some(mqtt) = self.mqtt.as_mut() {
+            mqtt.update(&self.state, update_positions);
+        }
+        if let Some(hooks) = self.hooks.as_mut() {
+            hooks.update(&self.state);
+        }
+        if let Some(notifier) = self.notifier.as_mut() {
+            notifier.update(&self.state, self.settings.ui.rotator_error);
+        }
+        if let (Some(api), true) = (&self.api, update_positions) {
+            api.send_event("status", &Status::new(&self.state));
         }
 
         if self.ticks % 60 == 0 {
@@ -385,13 +950,44 @@ impl Ui {
                 job.remove_finished_jobs();
             }
         }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.update(&self.state);
+        }
+
+        if u64::from(self.ticks) % self.settings.status_interval.max(1) == 0 {
+            self.write_status();
+        }
+    }
+
+    fn write_status(&mut self) {
+        let output = match self.status_output.as_mut() {
+            Some(output) => output,
+            None => return,
+        };
+
+        let result = serde_json::to_writer(&mut *output, &Status::new(&self.state))
+            .map_err(io::Error::from)
+            .and_then(|_| output.write_all(b"\n"))
+            .and_then(|_| output.flush());
+
+        if let Err(err) = result {
+            error!("Failed to write status, status output stopped: {}", err);
+            self.status_output = None;
+        }
     }
 
     fn update_jobs(&mut self) {
+        let network = match &mut self.network {
+            Some(network) => network,
+            None => return,
+        };
+
         trace!("Requesting jobs update");
 
         for id in self.state.stations.keys() {
-            self.network.send(satnogs::Command::GetJobs(*id)).unwrap();
+            network.send(satnogs::Command::GetJobs(*id)).unwrap();
+            network.send(satnogs::Command::GetStationInfo(*id)).unwrap();
         }
         self.last_job_update = std::time::Instant::now();
     }
@@ -424,7 +1020,9 @@ impl Ui {
             self.draw()?;
 
             if self.shutdown {
-                self.terminal.clear()?;
+                if let Some(terminal) = self.terminal.as_mut() {
+                    terminal.clear()?;
+                }
                 break;
             }
         }
@@ -433,19 +1031,57 @@ impl Ui {
     }
 }
 
-fn render_waterfall<T: Backend>(
+/// Puts stdout into raw mode and fails gracefully if the terminal is smaller than
+/// `width` x `height`.
+pub fn init_terminal(width: u16, height: u16) -> Result<Terminal<TermBackend>> {
+    let stdout = io::stdout()
+        .into_raw_mode()
+        .context("failed to put stdout into raw mode")?;
+    let stdout = MouseTerminal::from(stdout);
+    let backend = TermionBackend::new(stdout);
+    let mut terminal = Terminal::new(backend).context("failed to create terminal")?;
+
+    let size = terminal.size().context("failed to get terminal size")?;
+    if size.width < width || size.height < height {
+        bail!(
+            "Terminal size is under {} columns or {} lines",
+            width,
+            height
+        );
+    }
+
+    terminal.clear().context("failed to clear terminal")?;
+    terminal.hide_cursor().context("failed to hide cursor")?;
+
+    Ok(terminal)
+}
+
+pub fn render_waterfall<T: Backend>(
     t: &mut Frame<T>,
     rect: Rect,
-    _frequencies: &[f32],
+    frequencies: &[f32],
     data: &[(i64, Vec<f32>)],
-    db_range: [f32; 2],
+    view: &SpectrumView,
 ) {
+    let db_range = view.db_range;
+
     Waterfall::default()
         .data(data)
+        .frequencies(frequencies)
         .bounds(db_range)
+        .color_mode(view.color_mode)
+        .colormap(view.colormap)
+        .viewport(view.viewport)
+        .reduction(view.reduction)
+        .axis_style(Style::default().fg(Color::DarkGray))
+        .marker(view.marker, Style::default().fg(Color::Yellow))
         .block(
             Block::default()
-                .title("Waterfall")
+                .title(&format!(
+                    "Waterfall ({}, {})",
+                    view.reduction.name(),
+                    view.colormap.name()
+                ))
                 .title_style(Style::default().fg(Color::Yellow))
                 .borders(Borders::TOP)
                 .border_style(Style::default().fg(Color::DarkGray)),
@@ -462,18 +1098,62 @@ fn render_waterfall<T: Backend>(
         .render(t, rect);
 }
 
-fn render_spectrum_plot<T: Backend>(
+pub fn render_spectrum_plot<T: Backend>(
     t: &mut Frame<T>,
     rect: Rect,
     frequencies: &[f32],
     data: &[(i64, Vec<f32>)],
-    db_range: [f32; 2],
-    zoom: f32,
+    traces: &SpectrumTraces,
+    view: &SpectrumView,
 ) {
+    let db_range = view.db_range;
+    let viewport = view.viewport;
+    let bounds = viewport.bounds(frequencies);
+    let labels = (0..5)
+        .map(|index| {
+            let frequency = bounds[0] + index as f32 * (bounds[1] - bounds[0]) / 4.0;
+            format!("{:.1}", frequency / 1000.0)
+        })
+        .collect::<Vec<_>>();
+
+    let points = |row: &[f32]| {
+        frequencies
+            .iter()
+            .zip(row)
+            .filter(|(x, _)| **x >= bounds[0] && **x <= bounds[1])
+            .map(|(x, y)| (*x as f64, *y as f64))
+            .collect::<Vec<_>>()
+    };
+
+    // the traces are drawn in this order, the average stays visible on top of
+    // the noisy current row
+    let mut title = format!("Spectrum (x{:.*}", 1, viewport.zoom());
+    let mut traces_data = vec![];
+    if view.peak_hold_trace && !traces.peak_hold.is_empty() {
+        traces_data.push((points(&traces.peak_hold), Color::Red));
+        title.push_str(", peak");
+    }
+    traces_data.push((points(&data.last().unwrap().1), Color::Cyan));
+    if view.average_trace && !traces.average.is_empty() {
+        traces_data.push((points(&traces.average), Color::Yellow));
+        title.push_str(", avg");
+    }
+    title.push(')');
+
+    let datasets = traces_data
+        .iter()
+        .map(|(points, color)| {
+            Dataset::default()
+                .marker(Marker::Braille)
+                .style(Style::default().fg(*color))
+                .data(points)
+        })
+        .collect::<Vec<_>>();
+
     Chart::default()
         .block(
             Block::default()
-                .title(&format!("Spectrum (x{:.*})", 1, zoom))
+                .title(&title)
                 .title_style(Style::default().fg(Color::Yellow))
                 .borders(Borders::TOP)
                 .border_style(Style::default().fg(Color::DarkGray)),
@@ -483,23 +1163,8 @@ fn render_spectrum_plot<T: Backend>(
                 .title("Frequency (kHz)")
                 .title_style(Style::default().fg(Color::DarkGray))
                 .style(Style::default().fg(Color::DarkGray))
-                .bounds([
-                    (*frequencies.first().unwrap() / zoom) as f64,
-                    (*frequencies.last().unwrap() / zoom) as f64,
-                ])
-                .labels(&[
-                    &format!("{}", (frequencies.first().unwrap() / 1000.0 / zoom).floor()),
-                    &format!(
-                        "{}",
-                        (frequencies.first().unwrap() / 1000.0 / 2.0 / zoom).floor()
-                    ),
-                    &format!("{}", 0),
-                    &format!(
-                        "{}",
-                        (frequencies.last().unwrap() / 1000.0 / 2.0 / zoom).ceil()
-                    ),
-                    &format!("{}", (frequencies.last().unwrap() / 1000.0 / zoom).ceil()),
-                ])
+                .bounds([bounds[0] as f64, bounds[1] as f64])
+                .labels(&labels)
                 .labels_style(Style::default().fg(Color::DarkGray)),
         )
         .y_axis(
@@ -515,40 +1180,155 @@ fn render_spectrum_plot<T: Backend>(
                 ])
                 .labels_style(Style::default().fg(Color::DarkGray)),
         )
-        .datasets(&[Dataset::default()
-            .marker(Marker::Braille)
-            .style(Style::default().fg(Color::Cyan))
-            .data(
-                frequencies
-                    .iter()
-                    .zip(&data.last().unwrap().1)
-                    .map(|(x, y)| (*x as f64, *y as f64))
-                    .collect::<Vec<_>>()
-                    .as_ref(),
-            )])
+        .datasets(&datasets)
         .render(t, rect);
 }
 
 fn render_map_view<T: Backend>(
     t: &mut Frame<T>,
     rect: Rect,
+    state: &State,
     station: &Station,
-    ground_tracks: usize,
-    footprint: bool,
+    view: &MapView,
 ) {
+    let next_job = station.jobs.iter().next();
+    let upcoming = match view.mode {
+        MapMode::Next => vec![],
+        _ => state.upcoming_jobs(view.station(station.id()), view.until),
+    };
+    // the satellite of the next job is drawn like in the single satellite view
+    let others = upcoming.iter().copied().filter(|job| {
+        next_job.map(|next| next.observation.norad_cat_id) != Some(job.observation.norad_cat_id)
+    });
+    let now = Utc::now();
+
+    let focus = match view.center {
+        MapCenter::World => None,
+        MapCenter::Station => Some((station.info.lng, station.info.lat)),
+        MapCenter::Satellite => next_job.map(|job| (job.sat().lon_deg, job.sat().lat_deg)),
+    };
+    let viewport = MapViewport::new(
+        view.projection,
+        (station.info.lat, station.info.lng),
+        focus,
+        view.pan,
+        view.zoom,
+    );
+    // braille dots are about square, a cell has two columns and four rows of them
+    let aspect = f64::from(rect.width) / f64::from(rect.height.max(1) * 2);
+    let (x_bounds, y_bounds) = viewport.bounds(aspect);
+
     Canvas::default()
         .paint(|ctx| {
-            ctx.draw(&Map {
-                color: COL_LIGHT_BG,
-                resolution: MapResolution::High,
+            ctx.draw(&Projected {
+                shape: &Map {
+                    color: COL_LIGHT_BG,
+                    resolution: MapResolution::High,
+                },
+                viewport: &viewport,
             });
 
-            ctx.print(station.info.lng, station.info.lat, DOT, Color::LightCyan);
+            let terminator = sun::terminator(now);
+            ctx.draw(&Projected {
+                shape: &Points {
+                    coords: &terminator,
+                    color: COL_ECLIPSE,
+                },
+                viewport: &viewport,
+            });
+            let (sun_lat, sun_lon) = sun::subsolar_point(now);
+            print_projected(
+                ctx,
+                &viewport,
+                (sun_lon, sun_lat),
+                String::from("☀"),
+                Color::Yellow,
+            );
+
+            // connect the satellites with the stations which can hear them
+            ctx.layer();
+            let satellites = next_job
+                .into_iter()
+                .map(|job| (job, Color::LightRed))
+                .chain(others.clone().zip(SATELLITE_COLORS.iter().copied().cycle()));
+            for (job, color) in satellites {
+                let sat = job.sat();
+                for other in state.stations.values() {
+                    if job.vessel.covers(other.info.lat, other.info.lng) {
+                        // the short way, across the antimeridian if necessary
+                        let line = Line {
+                            x1: sat.lon_deg,
+                            y1: sat.lat_deg,
+                            x2: sat.lon_deg + wrap_longitude(other.info.lng - sat.lon_deg),
+                            y2: other.info.lat,
+                            color,
+                        };
+                        ctx.draw(&Projected {
+                            shape: &line,
+                            viewport: &viewport,
+                        });
+                    }
+                }
+            }
+
+            for other in state.stations.values() {
+                let color = match other.info.status {
+                    StationStatus::Online => Color::LightGreen,
+                    StationStatus::Testing => Color::LightYellow,
+                    StationStatus::Offline => Color::Red,
+                };
+                let label = if other.id() == station.id() {
+                    format!("◉─{}", other.name())
+                } else {
+                    format!("{}─{}", DOT, other.name())
+                };
+                print_projected(
+                    ctx,
+                    &viewport,
+                    (other.info.lng, other.info.lat),
+                    label,
+                    color,
+                );
+            }
 
-            if let Some(job) = station.jobs.iter().next() {
+            if view.mode != MapMode::Next {
                 ctx.print(
-                    job.sat().lon_deg,
-                    job.sat().lat_deg,
+                    x_bounds[0],
+                    y_bounds[0],
+                    format!(
+                        "{} jobs until {}",
+                        view.mode.name(),
+                        view.until.format("%H:%M UTC")
+                    ),
+                    COL_WHITE,
+                );
+            }
+
+            for (job, color) in others.clone().zip(SATELLITE_COLORS.iter().cycle()) {
+                if view.footprint {
+                    ctx.layer();
+                    ctx.draw(&Projected {
+                        shape: &Points {
+                            coords: &job.vessel.footprint,
+                            color: *color,
+                        },
+                        viewport: &viewport,
+                    });
+                }
+                let label = if job.start() <= now {
+                    format!("■─{}", job.vessel_name())
+                } else {
+                    format!("■─{} {}", job.vessel_name(), job.start().format("%H:%M"))
+                };
+                let position = (job.sat().lon_deg, job.sat().lat_deg);
+                print_projected(ctx, &viewport, position, label, *color);
+            }
+
+            if let Some(job) = next_job {
+                print_projected(
+                    ctx,
+                    &viewport,
+                    (job.sat().lon_deg, job.sat().lat_deg),
                     format!("■─{}", job.vessel_name()),
                     Color::LightRed,
                 );
@@ -557,30 +1337,60 @@ fn render_map_view<T: Backend>(
                 // plot future orbits first so the current orbit will be drawn on top
                 ground_track.color = Color::Cyan;
                 ground_track.coords =
-                    &job.vessel.ground_track[job.vessel.ground_track.len() / ground_tracks..];
-                ctx.draw(&ground_track);
+                    &job.vessel.ground_track[job.vessel.ground_track.len() / view.ground_tracks..];
+                ctx.draw(&Projected {
+                    shape: &ground_track,
+                    viewport: &viewport,
+                });
 
                 ctx.layer();
                 ground_track.color = Color::Yellow;
                 ground_track.coords =
-                    &job.vessel.ground_track[..job.vessel.ground_track.len() / ground_tracks];
-                ctx.draw(&ground_track);
+                    &job.vessel.ground_track[..job.vessel.ground_track.len() / view.ground_tracks];
+                ctx.draw(&Projected {
+                    shape: &ground_track,
+                    viewport: &viewport,
+                });
 
-                if footprint {
+                ctx.layer();
+                ground_track.color = COL_ECLIPSE;
+                ground_track.coords = &job.vessel.ground_track_eclipse;
+                ctx.draw(&Projected {
+                    shape: &ground_track,
+                    viewport: &viewport,
+                });
+
+                if view.footprint {
                     ctx.layer();
                     let footprint = Points {
                         coords: &job.vessel.footprint,
                         color: Color::Green,
                     };
-                    ctx.draw(&footprint);
+                    ctx.draw(&Projected {
+                        shape: &footprint,
+                        viewport: &viewport,
+                    });
                 }
             }
         })
-        .x_bounds([-180.0, 180.0])
-        .y_bounds([-90.0, 90.0])
+        .x_bounds(x_bounds)
+        .y_bounds(y_bounds)
         .render(t, rect);
 }
 
+/// Prints a label at a longitude and latitude of the map
+fn print_projected(
+    ctx: &mut Context,
+    viewport: &MapViewport,
+    (lon, lat): (f64, f64),
+    text: String,
+    color: Color,
+) {
+    if let Some((x, y)) = viewport.project(lon, lat) {
+        ctx.print(x, y, text, color);
+    }
+}
+
 fn render_polar_plot<T: Backend>(t: &mut Frame<T>, rect: Rect, job: &Job) -> Rect {
     let area = Layout::default()
         .direction(Direction::Vertical)
@@ -630,6 +1440,17 @@ fn render_polar_plot<T: Backend>(t: &mut Frame<T>, rect: Rect, job: &Job) -> Rec
             };
             ctx.draw(&points);
 
+            let eclipse = job
+                .vessel
+                .polar_track_eclipse
+                .iter()
+                .map(azel2xy)
+                .collect::<Vec<(f64, f64)>>();
+            ctx.draw(&Points {
+                coords: &eclipse,
+                color: COL_ECLIPSE,
+            });
+
             if let Some(aos_point) = aos_point {
                 ctx.print(aos_point.0, aos_point.1, DOT, Color::Green);
             }
@@ -712,12 +1533,9 @@ fn render_station_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &Stati
     ];
 
     let sys_info = &station.sys_info;
-    if let Some(cpu_load) = &sys_info.cpu_load {
+    if let Some(cpu_idle) = &sys_info.cpu_idle {
         let load = 100.0
-            - cpu_load
-                .iter()
-                .fold(0.0, |acc, load| acc + load.idle * 100.0)
-                / cpu_load.len() as f32;
+            - cpu_idle.iter().fold(0.0, |acc, idle| acc + idle * 100.0) / cpu_idle.len() as f32;
 
         station_info.extend_from_slice(&[
             Text::styled("CPU          ", Style::default().fg(Color::Cyan)),
@@ -742,7 +1560,7 @@ fn render_station_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &Stati
             Text::styled(
                 format!(
                     "{:19.1}",
-                    100.0 - (mem.free.as_u64() as f32 / mem.total.as_u64() as f32) * 100.0
+                    100.0 - (mem.free as f32 / mem.total as f32) * 100.0
                 ),
                 Style::default().fg(COL_WHITE),
             ),
@@ -768,7 +1586,13 @@ fn render_station_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &Stati
     area[1]
 }
 
-fn render_next_job_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &Station) -> Rect {
+fn render_next_job_view<T: Backend>(
+    t: &mut Frame<T>,
+    rect: Rect,
+    station: &Station,
+    rig_status: Option<&RigStatus>,
+    rig_thresholds: (f64, f64),
+) -> Rect {
     let mut jobs_rev = station.jobs.iter();
     let mut job_info = vec![];
 
@@ -820,7 +1644,54 @@ fn render_next_job_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &Stat
                 format!("{:19.3}", job.frequency_mhz()),
                 Style::default().fg(COL_WHITE),
             ),
-            Text::styled(" MHz\n\n", Style::default().fg(Color::LightGreen)),
+            Text::styled(" MHz\n", Style::default().fg(Color::LightGreen)),
+            Text::styled("Doppler      ", Style::default().fg(Color::Cyan)),
+            Text::styled(
+                format!("{:19.3}", job.doppler_frequency_mhz()),
+                Style::default().fg(COL_WHITE),
+            ),
+            Text::styled(" MHz\n", Style::default().fg(Color::LightGreen)),
+        ]);
+
+        let mut lines = 14;
+
+        if let Some(rig) = rig_status {
+            // the rig is only tuned to the satellite while the job is running
+            let rig_diff = (rig.frequency - job.doppler_frequency_mhz() * 1_000_000.0).abs();
+            let rig_color = match rig_diff {
+                _ if delta_t < chrono::Duration::zero() => COL_WHITE,
+                delta if delta < rig_thresholds.0 => COL_WHITE,
+                delta if delta < rig_thresholds.1 => Color::Yellow,
+                _ => Color::Red,
+            };
+
+            job_info.extend_from_slice(&[
+                Text::styled("Rig          ", Style::default().fg(Color::Cyan)),
+                Text::styled(
+                    format!("{:19.3}", rig.frequency / 1_000_000.0),
+                    Style::default().fg(rig_color),
+                ),
+                Text::styled(" MHz\n", Style::default().fg(Color::LightGreen)),
+                Text::styled("Rig Mode     ", Style::default().fg(Color::Cyan)),
+                Text::styled(
+                    format!("{:>19}\n", format!("{} {}", rig.mode, rig.passband)),
+                    Style::default().fg(COL_WHITE),
+                ),
+            ]);
+            lines += 2;
+
+            if let Some(strength) = rig.strength {
+                job_info.extend_from_slice(&[
+                    Text::styled("Signal       ", Style::default().fg(Color::Cyan)),
+                    Text::styled(format!("{:19}", strength), Style::default().fg(COL_WHITE)),
+                    Text::styled(" dB\n", Style::default().fg(Color::LightGreen)),
+                ]);
+                lines += 1;
+            }
+        }
+
+        job_info.extend_from_slice(&[
+            Text::styled("\n", Style::default()),
             Text::styled("Rise         ", Style::default().fg(Color::Cyan)),
             Text::styled(
                 format!("{:19.3}", job.observation.rise_azimuth),
@@ -841,7 +1712,7 @@ fn render_next_job_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &Stat
             Text::styled(" °\n", Style::default().fg(Color::LightGreen)),
         ]);
 
-        13
+        lines
     } else {
         job_info.push(Text::styled(
             "Next Job\n\n",
@@ -869,6 +1740,144 @@ fn render_next_job_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &Stat
     area[1]
 }
 
+fn render_recordings_view<T: Backend>(
+    t: &mut Frame<T>,
+    rect: Rect,
+    recordings: &[&Recording],
+    stalled: bool,
+) -> Rect {
+    let mut lines = 3 + recordings.len() as u16;
+    let mut recording_info = vec![Text::styled(
+        "Recordings\n\n",
+        Style::default().fg(Color::Yellow),
+    )];
+
+    for recording in recordings {
+        let rate_color = if recording.rate > 0.0 {
+            COL_WHITE
+        } else {
+            Color::Red
+        };
+
+        recording_info.extend_from_slice(&[
+            Text::styled(
+                format!("{:13}", recording.kind.name()),
+                Style::default().fg(Color::Cyan),
+            ),
+            Text::styled(
+                format!("{:>11}", format_bytes(recording.size as f64)),
+                Style::default().fg(COL_WHITE),
+            ),
+            Text::styled(
+                format!("{:>13}", format_bytes(recording.rate)),
+                Style::default().fg(rate_color),
+            ),
+            Text::styled("/s\n", Style::default().fg(Color::LightGreen)),
+        ]);
+    }
+
+    if stalled {
+        recording_info.push(Text::styled(
+            "No recording is growing\n",
+            Style::default().fg(Color::Red),
+        ));
+        lines += 1;
+    }
+
+    let area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(lines), Constraint::Min(0)].as_ref())
+        .split(rect);
+
+    Paragraph::new(recording_info.iter())
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .borders(Borders::RIGHT)
+                .border_style(Style::default().fg(COL_DARK_CYAN)),
+        )
+        .render(t, area[0]);
+
+    area[1]
+}
+
+fn render_signal_view<T: Backend>(
+    t: &mut Frame<T>,
+    rect: Rect,
+    report: &SignalReport,
+    snr_threshold: f32,
+) -> Rect {
+    let format_db = |value: Option<f32>| match value {
+        Some(value) => format!("{:>19.1}", value),
+        None => format!("{:>19}", "-"),
+    };
+
+    let snr_color = match report.last_peak {
+        Some(peak) if peak.snr >= snr_threshold => Color::LightGreen,
+        _ => COL_WHITE,
+    };
+
+    let (status, status_color) = if report.seen() {
+        ("Seen", Color::LightGreen)
+    } else {
+        ("Not seen", Color::Red)
+    };
+
+    let signal_info = vec![
+        Text::styled("Signal\n\n", Style::default().fg(Color::Yellow)),
+        Text::styled("Status       ", Style::default().fg(Color::Cyan)),
+        Text::styled(format!("{:>19}", status), Style::default().fg(status_color)),
+        Text::styled("\n", Style::default().fg(Color::LightGreen)),
+        Text::styled("Noise Floor  ", Style::default().fg(Color::Cyan)),
+        Text::styled(
+            format_db(report.noise_floor),
+            Style::default().fg(COL_WHITE),
+        ),
+        Text::styled(" dB\n", Style::default().fg(Color::LightGreen)),
+        Text::styled("SNR          ", Style::default().fg(Color::Cyan)),
+        Text::styled(
+            format_db(report.last_peak.map(|peak| peak.snr)),
+            Style::default().fg(snr_color),
+        ),
+        Text::styled(" dB\n", Style::default().fg(Color::LightGreen)),
+        Text::styled("Peak SNR     ", Style::default().fg(Color::Cyan)),
+        Text::styled(
+            format_db(report.peak.map(|peak| peak.snr)),
+            Style::default().fg(COL_WHITE),
+        ),
+        Text::styled(" dB\n", Style::default().fg(Color::LightGreen)),
+    ];
+
+    let area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(7), Constraint::Min(0)].as_ref())
+        .split(rect);
+
+    Paragraph::new(signal_info.iter())
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .borders(Borders::RIGHT)
+                .border_style(Style::default().fg(COL_DARK_CYAN)),
+        )
+        .render(t, area[0]);
+
+    area[1]
+}
+
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
 fn render_satellite_view<T: Backend>(
     t: &mut Frame<T>,
     rect: Rect,
@@ -933,6 +1942,19 @@ fn render_satellite_view<T: Backend>(
                 Style::default().fg(COL_WHITE),
             ),
             Text::styled(" km/s\n", Style::default().fg(Color::LightGreen)),
+            Text::styled("Illumination ", Style::default().fg(Color::Cyan)),
+            if job.vessel.is_eclipsed() {
+                Text::styled(
+                    format!("{:>19}", "Eclipse"),
+                    Style::default().fg(COL_ECLIPSE),
+                )
+            } else {
+                Text::styled(
+                    format!("{:>19}", "Sunlit"),
+                    Style::default().fg(Color::Yellow),
+                )
+            },
+            Text::styled("\n", Style::default().fg(Color::LightGreen)),
         ]);
 
         if let Some((azimuth, elevation)) = state.rotator_position {
@@ -986,7 +2008,7 @@ fn render_satellite_view<T: Backend>(
             ]);
         }
 
-        12
+        13
     };
 
     let area = Layout::default()
@@ -1077,6 +2099,52 @@ fn render_future_jobs_view<T: Backend>(t: &mut Frame<T>, rect: Rect, station: &S
     area[1]
 }
 
+fn render_signal_history_view<T: Backend>(t: &mut Frame<T>, rect: Rect, state: &State) {
+    let block = Block::default()
+        .borders(Borders::RIGHT | Borders::LEFT | Borders::TOP)
+        .border_style(Style::default().fg(COL_DARK_CYAN))
+        .title("Signal History")
+        .title_style(Style::default().fg(Color::Yellow));
+
+    let inner = block.inner(rect);
+    let empty_line = (0..inner.width).map(|_| " ").collect::<String>() + "\n";
+
+    // clear background of the history window
+    Paragraph::new(
+        (0..inner.height)
+            .map(|_| Text::raw(&empty_line))
+            .collect::<Vec<_>>()
+            .iter(),
+    )
+    .render(t, inner);
+
+    let mut history = vec![];
+    for report in state.signal_history.iter().take(inner.height as usize) {
+        let style = if report.seen() {
+            Style::default().fg(Color::LightGreen)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+
+        history.extend_from_slice(&[
+            Text::styled(
+                format!("#{:<8}", report.observation),
+                Style::default().fg(Color::Cyan),
+            ),
+            Text::styled(format!("{}\n", report), style),
+        ]);
+    }
+
+    if history.is_empty() {
+        history.push(Text::styled(
+            "No finished observations\n",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    Paragraph::new(history.iter()).block(block).render(t, rect);
+}
+
 fn render_log_view<T: Backend>(t: &mut Frame<T>, rect: Rect, logs: &LogQueue) {
     let block = Block::default()
         .borders(Borders::RIGHT | Borders::LEFT | Borders::TOP)
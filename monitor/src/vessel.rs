@@ -1,10 +1,20 @@
-use chrono::{DateTime, Utc};
+use crate::sun;
+
+use chrono::{DateTime, Duration, Utc};
 use gpredict::{Location, Predict, Sat, Tle};
 
+/// Points of a track, longitude and latitude or azimuth and elevation
+pub type Track = Vec<(f64, f64)>;
+
 pub struct Vessel {
     pub footprint: Vec<(f64, f64)>,
     pub ground_track: Vec<(f64, f64)>,
+    /// Points of the ground track where the satellite is in the shadow of the earth
+    pub ground_track_eclipse: Track,
     pub polar_track: Vec<(f64, f64)>,
+    /// Points of the polar track where the satellite is in the shadow of the earth
+    pub polar_track_eclipse: Track,
+    eclipsed: bool,
     pub id: u64,
     pub qth: Location,
     sat: Sat,
@@ -28,14 +38,18 @@ impl Vessel {
         };
 
         let mut predict = Predict::new(&tle, &qth);
-        let polar_track = calc_polar_track(&mut predict, aos, los);
+        let (polar_track, polar_track_eclipse) = calc_polar_track(&mut predict, aos, los);
 
         predict.update(None);
+        let eclipsed = is_eclipsed(&predict.sat, Utc::now());
 
         Vessel {
             footprint: vec![],
             ground_track: vec![],
+            ground_track_eclipse: vec![],
             polar_track,
+            polar_track_eclipse,
+            eclipsed,
             id,
             sat: predict.sat,
             tle,
@@ -51,18 +65,36 @@ impl Vessel {
         &self.sat
     }
 
+    #[cfg(test)]
+    pub fn sat_mut(&mut self) -> &mut Sat {
+        &mut self.sat
+    }
+
+    /// Whether the satellite is in the shadow of the earth
+    pub fn is_eclipsed(&self) -> bool {
+        self.eclipsed
+    }
+
     pub fn update_position(&mut self, orbits: u8) {
         let mut predict = Predict::new(&self.tle, &self.qth);
         predict.update(None);
         let update_ground_track =
             self.sat.orbit_nr != predict.sat.orbit_nr || self.ground_track.is_empty();
         self.sat = predict.sat;
+        self.eclipsed = is_eclipsed(&self.sat, Utc::now());
         if update_ground_track {
             self.update_ground_track(orbits);
         }
         self.update_footprint();
     }
 
+    /// Whether the satellite is above the horizon of a location, i.e. the
+    /// location lies within the footprint
+    pub fn covers(&self, lat_deg: f64, lon_deg: f64) -> bool {
+        central_angle((self.sat.lat_deg, self.sat.lon_deg), (lat_deg, lon_deg))
+            <= footprint_angle(self.sat.alt_km)
+    }
+
     pub fn update_footprint(&mut self) {
         use std::f64::consts::PI;
         let xkmper = 6.378_135E3;
@@ -130,20 +162,28 @@ impl Vessel {
         let mut current_orbit = self.sat.orbit_nr;
         let this_orbit = current_orbit;
         let mut time = hifitime::Epoch::now().unwrap();
+        let mut utc = Utc::now();
 
         while current_orbit == this_orbit {
             time = time - 10 * hifitime::Unit::Second;//time::Duration::seconds(10);
+            utc -= Duration::seconds(10);
             predict.update(Some(time));
             current_orbit = predict.sat.orbit_nr;
         }
 
         current_orbit = this_orbit;
         self.ground_track.clear();
+        self.ground_track_eclipse.clear();
         while current_orbit < this_orbit + orbits as u64 {
             time = time + 10 * hifitime::Unit::Second;//time::Duration::seconds(10);
+            utc += Duration::seconds(10);
             predict.update(Some(time));
             self.ground_track
                 .push((predict.sat.lon_deg, predict.sat.lat_deg));
+            if is_eclipsed(&predict.sat, utc) {
+                self.ground_track_eclipse
+                    .push((predict.sat.lon_deg, predict.sat.lat_deg));
+            }
             current_orbit = predict.sat.orbit_nr;
         }
     }
@@ -153,17 +193,62 @@ pub fn calc_polar_track(
     predict: &mut Predict,
     aos: DateTime<Utc>,
     los: DateTime<Utc>,
-) -> Vec<(f64, f64)> {
+) -> (Track, Track) {
     let mut polar_track = vec![];
+    let mut eclipse = vec![];
     let time_aos = hifitime::Epoch::from_unix_seconds(aos.timestamp() as f64);
     let time_los = hifitime::Epoch::from_unix_seconds(los.timestamp() as f64);
 
     let mut time = time_aos;
+    let mut utc = aos;
     while time <= time_los {
         predict.update(Some(time));
         polar_track.push((predict.sat.az_deg, predict.sat.el_deg));
+        if is_eclipsed(&predict.sat, utc) {
+            eclipse.push((predict.sat.az_deg, predict.sat.el_deg));
+        }
         time = time + 2 * hifitime::Unit::Second;
+        utc += Duration::seconds(2);
     }
 
-    polar_track
+    (polar_track, eclipse)
+}
+
+fn is_eclipsed(sat: &Sat, time: DateTime<Utc>) -> bool {
+    sun::is_eclipsed(sat.lat_deg, sat.lon_deg, sat.alt_km, time)
+}
+
+/// Angle between the sub-satellite point and the edge of the footprint in radians
+fn footprint_angle(alt_km: f64) -> f64 {
+    let xkmper = 6.378_135E3;
+    (xkmper / (xkmper + alt_km)).acos()
+}
+
+/// Great circle angle between two points given as latitude and longitude in
+/// degrees, in radians
+fn central_angle(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat_a, lat_b) = (a.0.to_radians(), b.0.to_radians());
+    let delta_lon = (a.1 - b.1).to_radians();
+
+    (lat_a.sin() * lat_b.sin() + lat_a.cos() * lat_b.cos() * delta_lon.cos())
+        .clamp(-1.0, 1.0)
+        .acos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footprint_covers_nearby_locations() {
+        // a satellite in 500 km sees about 22° of arc in every direction
+        let angle = footprint_angle(500.0).to_degrees();
+        assert!((angle - 21.98).abs() < 0.01, "{}", angle);
+
+        assert!(central_angle((48.0, 11.0), (48.0, 11.0)).abs() < 1e-9);
+        let across = central_angle((0.0, 179.0), (0.0, -179.0)).to_degrees();
+        assert!((across - 2.0).abs() < 1e-9, "{}", across);
+        let meridian = central_angle((60.0, 0.0), (30.0, 0.0)).to_degrees();
+        assert!((meridian - 30.0).abs() < 1e-9, "{}", meridian);
+    }
 }
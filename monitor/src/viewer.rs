@@ -0,0 +1,366 @@
+use anyhow::Context as _;
+use log::debug;
+use signal_hook::consts::signal::SIGWINCH;
+use signal_hook::iterator::Signals;
+use termion::input::TermRead;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Style};
+use tui::widgets::{Block, Paragraph, Text, Widget};
+use tui::Terminal;
+
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::thread;
+
+use crate::event::Event;
+use crate::export;
+use crate::settings::Settings;
+use crate::spectrum::{self, SpectrumTraces};
+use crate::ui::{self, SpectrumView, TermBackend};
+use crate::waterfall::{find_waterfall_files, WaterfallRecording};
+use crate::widgets::FrequencyViewport;
+use crate::Result;
+
+/// Displays waterfall files of past observations
+pub struct Viewer {
+    current: usize,
+    error: Option<String>,
+    events: Receiver<Event>,
+    files: Vec<PathBuf>,
+    page: usize,
+    position: usize,
+    recording: Option<WaterfallRecording>,
+    settings: Settings,
+    shutdown: bool,
+    size: Rect,
+    terminal: Terminal<TermBackend>,
+    /// First row of the average and peak hold traces
+    trace_start: usize,
+    viewport: FrequencyViewport,
+}
+
+impl Viewer {
+    pub fn new(paths: &[PathBuf], settings: Settings) -> Result<Self> {
+        let files = find_waterfall_files(paths)?;
+        let (sender, reciever) = sync_channel(100);
+
+        // Must be called before any threads are launched
+        let winch_send = sender.clone();
+        let mut signals =
+            Signals::new([SIGWINCH]).context("couldn't register resize signal handler")?;
+        thread::spawn(move || {
+            for _ in signals.forever() {
+                let _ = winch_send.send(Event::Resize);
+            }
+        });
+
+        thread::spawn(move || {
+            for event in ::std::io::stdin().events().flatten() {
+                let _ = sender.send(Event::Input(event));
+            }
+        });
+
+        let terminal = ui::init_terminal(80, 24)?;
+
+        let viewport = FrequencyViewport::new(settings.waterfall_zoom);
+
+        let mut viewer = Viewer {
+            current: 0,
+            error: None,
+            events: reciever,
+            files,
+            page: 0,
+            position: 0,
+            recording: None,
+            settings,
+            shutdown: false,
+            size: Rect::default(),
+            terminal,
+            trace_start: 0,
+            viewport,
+        };
+        viewer.load(0);
+
+        Ok(viewer)
+    }
+
+    fn load(&mut self, index: usize) {
+        self.current = index;
+        self.trace_start = 0;
+
+        match WaterfallRecording::from_file(&self.files[index]) {
+            Ok(recording) => {
+                self.position = recording.rows.len().saturating_sub(1);
+                self.recording = Some(recording);
+                self.error = None;
+            }
+            Err(err) => {
+                self.position = 0;
+                self.recording = None;
+                self.error = Some(format!("{:#}", err));
+            }
+        }
+    }
+
+    fn export(&mut self) {
+        let db_range = self.db_range();
+        if let Some(recording) = &self.recording {
+            let png = self.files[self.current].with_extension("png");
+            if let Err(err) =
+                export::waterfall_png(&png, &recording.frequencies, &recording.rows, db_range)
+            {
+                self.error = Some(format!("{:#}", err));
+            }
+        }
+    }
+
+    fn pan(&mut self, steps: i32) {
+        if let Some(recording) = &self.recording {
+            self.viewport.pan(&recording.frequencies, steps);
+        }
+    }
+
+    /// The configured dB range or the auto range of the rows up to the current
+    /// position
+    fn db_range(&self) -> [f32; 2] {
+        let ui = &self.settings.ui;
+        let auto_range = match &self.recording {
+            Some(recording) if ui.auto_range && !recording.rows.is_empty() => spectrum::auto_range(
+                &recording.rows[..=self.position],
+                ui.auto_range_low,
+                ui.auto_range_high,
+            ),
+            _ => None,
+        };
+
+        auto_range.unwrap_or([ui.db_min, ui.db_max])
+    }
+
+    /// Traces of the rows from the last reset up to the current position
+    fn traces(&self) -> SpectrumTraces {
+        let ui = &self.settings.ui;
+        match &self.recording {
+            Some(recording)
+                if (ui.average_trace || ui.peak_hold_trace) && !recording.rows.is_empty() =>
+            {
+                let start = self.trace_start.min(self.position);
+                SpectrumTraces::from_rows(&recording.rows[start..=self.position], ui.average_weight)
+            }
+            _ => SpectrumTraces::default(),
+        }
+    }
+
+    fn rows(&self) -> usize {
+        self.recording
+            .as_ref()
+            .map(|recording| recording.rows.len())
+            .unwrap_or(0)
+    }
+
+    fn scroll_older(&mut self, rows: usize) {
+        // keep at least one full page on screen
+        let oldest = self.page.min(self.rows()).saturating_sub(1);
+        self.position = self.position.saturating_sub(rows).max(oldest);
+    }
+
+    fn scroll_newer(&mut self, rows: usize) {
+        self.position = (self.position + rows).min(self.rows().saturating_sub(1));
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        let size = self
+            .terminal
+            .size()
+            .context("Failed to get terminal size")?;
+        if self.size != size {
+            self.terminal
+                .resize(size)
+                .context("Failed to resize terminal")?;
+            self.size = size;
+        }
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Percentage(40),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(self.size);
+
+        // the waterfall widget draws two rows per line
+        self.page = rows[2].height as usize * 2;
+
+        let spectrum_view = SpectrumView {
+            average_trace: self.settings.ui.average_trace,
+            color_mode: self.settings.ui.color_mode,
+            colormap: self.settings.ui.colormap,
+            db_range: self.db_range(),
+            marker: None,
+            peak_hold_trace: self.settings.ui.peak_hold_trace,
+            reduction: self.settings.ui.bin_reduction,
+            viewport: self.viewport,
+        };
+        let position = self.position;
+        let traces = self.traces();
+        let header = self.header();
+        let recording = &self.recording;
+
+        self.terminal
+            .draw(|mut f| {
+                Paragraph::new(header.iter())
+                    .block(Block::default())
+                    .render(&mut f, rows[0]);
+
+                if let Some(recording) = recording {
+                    if !recording.rows.is_empty() {
+                        let data = &recording.rows[..=position];
+
+                        ui::render_spectrum_plot(
+                            &mut f,
+                            rows[1],
+                            &recording.frequencies,
+                            data,
+                            &traces,
+                            &spectrum_view,
+                        );
+                        ui::render_waterfall(
+                            &mut f,
+                            rows[2],
+                            &recording.frequencies,
+                            data,
+                            &spectrum_view,
+                        );
+                    }
+                }
+            })
+            .context("Failed to draw to terminal")?;
+
+        Ok(())
+    }
+
+    fn header(&self) -> Vec<Text<'static>> {
+        let mut header = vec![
+            Text::styled(
+                format!("{}/{} ", self.current + 1, self.files.len()),
+                Style::default().fg(Color::Cyan),
+            ),
+            Text::styled(
+                format!("{}\n", self.files[self.current].display()),
+                Style::default().fg(Color::Yellow),
+            ),
+        ];
+
+        if let Some(error) = &self.error {
+            header.push(Text::styled(
+                format!("{}\n", error),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
+        if let Some(recording) = &self.recording {
+            if let Some(observation) = recording.observation {
+                header.extend_from_slice(&[
+                    Text::styled("Observation ", Style::default().fg(Color::Cyan)),
+                    Text::styled(
+                        format!("#{}  ", observation),
+                        Style::default().fg(Color::White),
+                    ),
+                ]);
+            }
+
+            header.extend_from_slice(&[
+                Text::styled("Start ", Style::default().fg(Color::Cyan)),
+                Text::styled(
+                    format!("{}  ", recording.timestamp.format("%Y-%m-%d %H:%M:%S")),
+                    Style::default().fg(Color::White),
+                ),
+                Text::styled("Center ", Style::default().fg(Color::Cyan)),
+                Text::styled(
+                    format!("{:.3}", recording.center_freq / 1_000_000.0),
+                    Style::default().fg(Color::White),
+                ),
+                Text::styled(" MHz\n", Style::default().fg(Color::LightGreen)),
+            ]);
+
+            if let Some((timestamp, _)) = recording.rows.get(self.position) {
+                header.extend_from_slice(&[
+                    Text::styled("Row ", Style::default().fg(Color::Cyan)),
+                    Text::styled(
+                        format!("{}/{}  ", self.position + 1, recording.rows.len()),
+                        Style::default().fg(Color::White),
+                    ),
+                    Text::styled("Time ", Style::default().fg(Color::Cyan)),
+                    Text::styled(
+                        format!("{:.1}", *timestamp as f64 / 1_000_000.0),
+                        Style::default().fg(Color::White),
+                    ),
+                    Text::styled(" s\n", Style::default().fg(Color::LightGreen)),
+                ]);
+            } else {
+                header.push(Text::styled("No data\n", Style::default().fg(Color::Red)));
+            }
+        }
+
+        header
+    }
+
+    fn handle_input(&mut self, event: &::termion::event::Event) {
+        use termion::event::Event::*;
+        use termion::event::Key::*;
+
+        match *event {
+            Key(Ctrl('c')) | Key(Char('q')) => self.shutdown = true,
+            Key(Down) | Key(Char('j')) => self.scroll_older(1),
+            Key(Up) | Key(Char('k')) => self.scroll_newer(1),
+            Key(PageDown) => self.scroll_older(self.page / 2),
+            Key(PageUp) => self.scroll_newer(self.page / 2),
+            Key(Home) => self.scroll_older(self.rows()),
+            Key(End) => self.scroll_newer(self.rows()),
+            Key(Char('p')) => self.export(),
+            Key(Char('\t')) => self.load((self.current + 1) % self.files.len()),
+            Key(BackTab) => self.load((self.current + self.files.len() - 1) % self.files.len()),
+            Key(Char('+')) => self.viewport.zoom_in(),
+            Key(Char('-')) => self.viewport.zoom_out(),
+            Key(Left) => self.pan(-1),
+            Key(Right) => self.pan(1),
+            Key(Char('0')) => self.viewport.reset(),
+            Key(Char('m')) => {
+                self.settings.ui.bin_reduction = self.settings.ui.bin_reduction.next();
+            }
+            Key(Char('c')) => self.settings.ui.colormap = self.settings.ui.colormap.next(),
+            Key(Char('a')) => self.settings.ui.auto_range = !self.settings.ui.auto_range,
+            Key(Char('e')) => self.settings.ui.average_trace = !self.settings.ui.average_trace,
+            Key(Char('x')) => {
+                self.settings.ui.peak_hold_trace = !self.settings.ui.peak_hold_trace;
+            }
+            Key(Char('r')) => self.trace_start = self.position,
+            Key(key) => {
+                debug!("Key Event: {:?}", key);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn run(mut self) -> Result<()> {
+        self.draw()?;
+
+        while let Ok(event) = self.events.recv() {
+            if let Event::Input(event) = event {
+                self.handle_input(&event);
+            }
+
+            self.draw()?;
+
+            if self.shutdown {
+                self.terminal.clear()?;
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
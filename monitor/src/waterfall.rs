@@ -1,35 +1,66 @@
 use crate::event::Event;
 use crate::Result;
 
-use anyhow::bail;
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use anyhow::{bail, Context};
+use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt};
 use chrono::{DateTime, FixedOffset};
-use crossbeam_channel::{unbounded, Receiver};
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError};
 use itertools_num::linspace;
 use lazy_static::lazy_static;
 use notify::{recommended_watcher, Event as RawEvent, RecursiveMode, Watcher};
 use regex::Regex;
 
-use std::fs::{File, OpenOptions};
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::ops::RangeInclusive;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::SyncSender;
 use std::thread;
+use std::time::{Duration, Instant};
 
 const HEADER_SIZE: u64 = 32 + 20;
 
+const FFT_SIZE_RANGE: RangeInclusive<u32> = 2..=65_536;
+const NFFT_PER_ROW_RANGE: RangeInclusive<u32> = 1..=100_000;
+const SAMPLE_RATE_RANGE: RangeInclusive<u32> = 1..=100_000_000;
+
+/// Interval of the data path scans which catch what the file system events miss
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// A waterfall file without new rows for this long is considered finished
+const STALE_AFTER: Duration = Duration::from_secs(60);
+
 lazy_static! {
     static ref RE: Regex = Regex::new(r".*/.*receiving_waterfall_(\d+)_.*\.dat.*").unwrap();
 }
 
+/// Byte order of the host which wrote the waterfall file
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Endianness {
+    Big,
+    Little,
+}
+
+impl Endianness {
+    fn from_flag(flag: u32) -> Option<Self> {
+        match flag {
+            0 => Some(Endianness::Little),
+            1 => Some(Endianness::Big),
+            _ => None,
+        }
+    }
+}
+
 #[allow(unused)]
 struct WaterfallHeader {
     /// Center frequency, the frequency your SDR is tuned to
     center_freq: f32,
-    // This should probably be an enum, not sure how to parse it yet
-    endianess: u32,
+    /// Byte order of the spectrum rows
+    endianess: Endianness,
     /// FFT size
     fft_size: u32,
-    // not sure what this does
+    /// Number of FFTs averaged into one spectrum row
     nfft_per_row: u32,
     /// Sample rate
     sample_rate: u32,
@@ -45,20 +76,88 @@ impl WaterfallHeader {
         let mut buf = [0; 32];
         reader.read_exact(&mut buf)?;
         let timestamp = parse_timestamp(&buf)?;
-        let fft_size = reader.read_u32::<BigEndian>()?;
-        let sample_rate = reader.read_u32::<BigEndian>()?;
-        let nfft_per_row = reader.read_u32::<BigEndian>()?;
-        let center_freq = reader.read_f32::<BigEndian>()?;
-        let endianess = reader.read_u32::<BigEndian>()?;
-
-        Ok(WaterfallHeader {
-            center_freq,
+
+        let mut buf = [0; 20];
+        reader.read_exact(&mut buf)?;
+
+        // The satnogs-client writes the header in network byte order and sets the
+        // endianness flag to the byte order of the spectrum rows. If the flag doesn't
+        // make sense in network byte order the whole header was written in the byte
+        // order of the host. A flag of 0 reads the same in both byte orders, so the
+        // other byte order is tried if the fields are out of range.
+        let big_endian = Endianness::from_flag(BigEndian::read_u32(&buf[16..]))
+            .map(|endianess| Self::from_fields::<BigEndian>(&buf, endianess, timestamp));
+        let little_endian = Endianness::from_flag(LittleEndian::read_u32(&buf[16..]))
+            .map(|endianess| Self::from_fields::<LittleEndian>(&buf, endianess, timestamp));
+
+        match (big_endian, little_endian) {
+            (Some(Ok(header)), _) | (_, Some(Ok(header))) => Ok(header),
+            (Some(Err(err)), _) | (None, Some(Err(err))) => Err(err),
+            (None, None) => bail!(
+                "invalid endianness flag {:#010x}",
+                BigEndian::read_u32(&buf[16..])
+            ),
+        }
+    }
+
+    fn from_fields<E: ByteOrder>(
+        buf: &[u8],
+        endianess: Endianness,
+        timestamp: DateTime<FixedOffset>,
+    ) -> Result<Self> {
+        let header = WaterfallHeader {
+            fft_size: E::read_u32(&buf[0..]),
+            sample_rate: E::read_u32(&buf[4..]),
+            nfft_per_row: E::read_u32(&buf[8..]),
+            center_freq: E::read_f32(&buf[12..]),
             endianess,
-            fft_size,
-            nfft_per_row,
-            sample_rate,
             timestamp,
-        })
+        };
+        header.validate()?;
+
+        Ok(header)
+    }
+
+    /// Frequency offset of each FFT bin relative to the center frequency
+    fn frequencies(&self) -> Vec<f32> {
+        linspace::<f32>(
+            -0.5 * self.sample_rate as f32,
+            0.5 * self.sample_rate as f32,
+            self.fft_size as usize,
+        )
+        .collect()
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !FFT_SIZE_RANGE.contains(&self.fft_size) {
+            bail!(
+                "FFT size {} out of range {:?}",
+                self.fft_size,
+                FFT_SIZE_RANGE
+            );
+        }
+
+        if !SAMPLE_RATE_RANGE.contains(&self.sample_rate) {
+            bail!(
+                "sample rate {} out of range {:?}",
+                self.sample_rate,
+                SAMPLE_RATE_RANGE
+            );
+        }
+
+        if !NFFT_PER_ROW_RANGE.contains(&self.nfft_per_row) {
+            bail!(
+                "FFTs per row {} out of range {:?}",
+                self.nfft_per_row,
+                NFFT_PER_ROW_RANGE
+            );
+        }
+
+        if !self.center_freq.is_finite() {
+            bail!("invalid center frequency {}", self.center_freq);
+        }
+
+        Ok(())
     }
 }
 
@@ -71,204 +170,537 @@ fn parse_timestamp(buf: &[u8]) -> Result<DateTime<FixedOffset>> {
     Ok(datetime)
 }
 
+/// A waterfall file written by the satnogs-client during a past observation
+pub struct WaterfallRecording {
+    pub observation: Option<u64>,
+    pub center_freq: f32,
+    pub timestamp: DateTime<FixedOffset>,
+    pub frequencies: Vec<f32>,
+    pub rows: Vec<(i64, Vec<f32>)>,
+}
+
+impl WaterfallRecording {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open waterfall file {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+        let header = WaterfallHeader::from_reader(&mut reader)
+            .with_context(|| format!("Invalid waterfall header in {}", path.display()))?;
+
+        let mut rows = vec![];
+        loop {
+            let row = match header.endianess {
+                Endianness::Big => read_row::<BigEndian, _>(&mut reader, header.fft_size as u64),
+                Endianness::Little => {
+                    read_row::<LittleEndian, _>(&mut reader, header.fft_size as u64)
+                }
+            };
+
+            match row {
+                Ok(row) => rows.push(row),
+                // the last row might be incomplete if the satnogs-client was stopped
+                Err(err) => match err.downcast_ref::<std::io::Error>() {
+                    Some(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+                    _ => return Err(err),
+                },
+            }
+        }
+
+        Ok(WaterfallRecording {
+            observation: observation_id(path),
+            center_freq: header.center_freq,
+            timestamp: header.timestamp,
+            frequencies: header.frequencies(),
+            rows,
+        })
+    }
+}
+
+/// Waterfall rows of the running observation with bounded memory usage.
+///
+/// Once more than `max_rows` rows are stored the older half of the buffer is
+/// decimated by merging pairs of rows. Recent rows keep their full resolution
+/// while the start of a long pass is still available at a lower time resolution.
+pub struct WaterfallBuffer {
+    max_rows: usize,
+    rows: Vec<(i64, Vec<f32>)>,
+}
+
+impl WaterfallBuffer {
+    pub fn new(max_rows: usize) -> Self {
+        WaterfallBuffer {
+            // we need at least a few rows to merge
+            max_rows: max_rows.max(4),
+            rows: vec![],
+        }
+    }
+
+    pub fn push(&mut self, row: (i64, Vec<f32>)) {
+        self.rows.push(row);
+
+        if self.rows.len() > self.max_rows {
+            self.decimate();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn rows(&self) -> &[(i64, Vec<f32>)] {
+        &self.rows
+    }
+
+    /// Merges pairs of rows in the older half, the merged row keeps the timestamp
+    /// of the older row and the maximum power of each bin
+    fn decimate(&mut self) {
+        let half = self.rows.len() / 2 / 2 * 2;
+        let merged = self
+            .rows
+            .drain(..half)
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|pair| {
+                let (timestamp, first) = &pair[0];
+                let (_, second) = &pair[1];
+                let power = first
+                    .iter()
+                    .zip(second)
+                    .map(|(first, second)| first.max(*second))
+                    .collect();
+
+                (*timestamp, power)
+            })
+            .collect::<Vec<_>>();
+
+        self.rows.splice(..0, merged);
+    }
+}
+
+/// Collects the waterfall files from the given paths. Directories are searched for
+/// waterfall files written by the satnogs-client, files are taken as they are.
+pub fn find_waterfall_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for path in paths {
+        if path.is_dir() {
+            let mut entries = vec![];
+            for entry in std::fs::read_dir(path)? {
+                let entry = entry?.path();
+                if entry.is_file() && RE.is_match(entry.to_str().unwrap_or("")) {
+                    entries.push(entry);
+                }
+            }
+
+            // the file names contain the observation start, so this is chronological
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    if files.is_empty() {
+        bail!("No waterfall files found");
+    }
+
+    Ok(files)
+}
+
+/// Position of the first row to stream from a waterfall file of `size` bytes, so
+/// that at most `max_rows` complete rows are left to read
+fn resume_position(size: u64, fft_size: u64, max_rows: u64) -> u64 {
+    let row_size = fft_size * 4 + 8;
+    let rows = size.saturating_sub(HEADER_SIZE) / row_size;
+
+    HEADER_SIZE + rows.saturating_sub(max_rows) * row_size
+}
+
+/// A waterfall file which is being written by the satnogs-client
 struct WaterfallFile {
+    endianess: Endianness,
     fft_size: u64,
-    _observation: u64,
+    /// Inode of the file, a new inode at the same path means the file was replaced
+    inode: u64,
+    /// Last time a row was read from the file
+    last_row: Instant,
+    path: PathBuf,
     reader: BufReader<File>,
 }
 
+impl WaterfallFile {
+    /// Opens a waterfall file and seeks to the row boundary at most `max_rows` rows
+    /// before its end. Returns `None` if the header hasn't been written yet.
+    fn open(path: &Path, max_rows: u64) -> Result<Option<(Self, WaterfallHeader)>> {
+        let file = File::open(path)?;
+        let metadata = file.metadata()?;
+        if metadata.len() < HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let mut reader = BufReader::new(file);
+        let header =
+            WaterfallHeader::from_reader(&mut reader).context("Invalid waterfall header")?;
+        let fft_size = header.fft_size as u64;
+        reader.seek(SeekFrom::Start(resume_position(
+            metadata.len(),
+            fft_size,
+            max_rows,
+        )))?;
+
+        let file = WaterfallFile {
+            endianess: header.endianess,
+            fft_size,
+            inode: metadata.ino(),
+            last_row: Instant::now(),
+            path: path.to_path_buf(),
+            reader,
+        };
+
+        Ok(Some((file, header)))
+    }
+
+    fn row_size(&self) -> u64 {
+        self.fft_size * 4 + 8
+    }
+
+    /// Whether the file at the path has been removed, replaced or truncated
+    fn is_replaced(&mut self) -> Result<bool> {
+        let metadata = match self.path.metadata() {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(true),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(metadata.ino() != self.inode || metadata.len() < self.reader.stream_position()?)
+    }
+
+    /// Reads all complete rows written since the last call
+    fn read_rows(&mut self) -> Result<Vec<(i64, Vec<f32>)>> {
+        let size = self.reader.get_ref().metadata()?.len();
+        let position = self.reader.stream_position()?;
+
+        let mut rows = vec![];
+        for _ in 0..size.saturating_sub(position) / self.row_size() {
+            rows.push(match self.endianess {
+                Endianness::Big => read_row::<BigEndian, _>(&mut self.reader, self.fft_size)?,
+                Endianness::Little => read_row::<LittleEndian, _>(&mut self.reader, self.fft_size)?,
+            });
+        }
+
+        if !rows.is_empty() {
+            self.last_row = Instant::now();
+        }
+
+        Ok(rows)
+    }
+}
+
+/// Streams the waterfall files written into a satnogs-client data path. Several
+/// flowgraphs may write waterfalls at the same time, so the open files are
+/// tracked by their observation.
+///
+/// File system events are used to pick up new rows immediately, but the data
+/// path is polled as well. This finds waterfalls that were started before the
+/// monitor and keeps working if events are missing or not supported.
 pub struct WaterfallWatcher {
     event_tx: SyncSender<Event>,
-    file: Option<WaterfallFile>,
+    files: HashMap<u64, WaterfallFile>,
+    /// Waterfall files which were closed, they are only opened again if they
+    /// are replaced
+    finished: HashSet<PathBuf>,
+    /// Waterfall files with an invalid header, they are not opened again
+    invalid: HashSet<PathBuf>,
+    last_poll: Instant,
+    /// Rows read at most from a waterfall that is already in progress
+    max_rows: u64,
+    path: PathBuf,
+    /// Station writing into the data path if only one does
+    station: Option<u64>,
     watcher_rx: Receiver<std::result::Result<RawEvent, notify::Error>>,
-    _watcher: notify::RecommendedWatcher,
+    _watcher: Option<notify::RecommendedWatcher>,
 }
 
 impl WaterfallWatcher {
-    pub fn new(path: &str, event_tx: SyncSender<Event>) -> Result<Self> {
+    pub fn new(
+        path: &str,
+        station: Option<u64>,
+        max_rows: usize,
+        event_tx: SyncSender<Event>,
+    ) -> Result<Self> {
         let (watcher_tx, watcher_rx) = unbounded();
 
-        let mut watcher = recommended_watcher(move |evt| watcher_tx.send(evt).unwrap())?;
-        watcher.watch(std::path::Path::new(path), RecursiveMode::NonRecursive)?;
+        let watcher = recommended_watcher(move |evt| {
+            let _ = watcher_tx.send(evt);
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(Path::new(path), RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+        let watcher = match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                log::warn!(
+                    "Failed to watch {} ({}), polling for waterfall files instead",
+                    path,
+                    err
+                );
+                None
+            }
+        };
 
         Ok(WaterfallWatcher {
             event_tx,
-            file: None,
+            files: HashMap::new(),
+            finished: HashSet::new(),
+            invalid: HashSet::new(),
+            last_poll: Instant::now(),
+            max_rows: max_rows as u64,
+            path: PathBuf::from(path),
+            station,
             watcher_rx,
             _watcher: watcher,
         })
     }
 
     pub fn run(&mut self) -> Result<()> {
+        // pick up a waterfall which is already being written
+        self.poll()?;
+
         loop {
-            match self.watcher_rx.recv() {
+            match self.watcher_rx.recv_timeout(POLL_INTERVAL) {
                 Ok(Ok(event)) => {
-                    let (kind, paths) = (event.kind, &event.paths);
-                    log::trace!("EventKind: {:?} Paths: {:?}", kind, paths);
-                    if paths.is_empty() {
-                        continue;
-                    }
-                    let path = &paths[0];
-                    // we are only interested in waterfall files
-                    if let Some(obs_id) = RE.captures(path.to_str().unwrap_or("")) {
-                        let observation: u64 = obs_id[1].parse().unwrap();
-
-                        match kind {
-                            // a new waterfall was created
-                            // wait until the header has been written and read it
-                            notify::EventKind::Create(notify::event::CreateKind::File) => {
-                                if let Err(err) = self.on_waterfall_file_created(observation, &path)
-                                {
-                                    log::error!(
-                                        "Failed to open waterfall file {}: {}",
-                                        path.to_str().unwrap_or(""),
-                                        err
-                                    );
-                                    continue;
-                                }
-                            }
-
-                            // some data has been written, check if it's at least one complete
-                            // spectrum line and send it to the ui
-                            notify::EventKind::Modify(notify::event::ModifyKind::Data(_)) => {
-                                if let Err(err) = self.on_waterfall_data_changed() {
-                                    log::error!("Failed to send waterfall data event: {}", err);
-                                }
-                            }
-
-                            // the waterfall is closed by the satnogs client
-                            // all data has been written and was read here so we can discard the
-                            // file notify the ui
-                            notify::EventKind::Access(notify::event::AccessKind::Close(_)) => {
-                                if let Err(err) = self.on_waterfall_closed(observation) {
-                                    log::error!("Failed to send waterfall closing event: {}", err);
-                                }
-                            }
-                            _ => {}
-                        }
+                    log::trace!("EventKind: {:?} Paths: {:?}", event.kind, event.paths);
+                    if let Some(path) = event.paths.first() {
+                        self.on_file_event(event.kind, path)?;
                     }
                 }
-                Ok(Err(err)) => {
-                    log::error!("Notify error: {}. Stopping watcher.", err);
-                    break;
+                Ok(Err(err)) => log::error!("Notify error: {}", err),
+                Err(RecvTimeoutError::Timeout) => {}
+                // without file system events we keep polling
+                Err(RecvTimeoutError::Disconnected) => thread::sleep(POLL_INTERVAL),
+            }
+
+            if self.last_poll.elapsed() >= POLL_INTERVAL {
+                self.poll()?;
+            }
+        }
+    }
+
+    fn on_file_event(&mut self, kind: notify::EventKind, path: &Path) -> Result<()> {
+        use notify::event::{AccessKind, EventKind, ModifyKind};
+
+        // we are only interested in waterfall files
+        let observation = match observation_id(path) {
+            Some(observation) => observation,
+            None => return Ok(()),
+        };
+
+        match kind {
+            // a new waterfall was created or some data has been written, send all
+            // complete spectrum rows to the ui
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Data(_)) => {
+                self.update(observation, path)
+            }
+
+            // the waterfall is closed by the satnogs client or moved away, all data
+            // has been written and was read here so we can discard the file
+            EventKind::Access(AccessKind::Close(_))
+            | EventKind::Modify(ModifyKind::Name(_))
+            | EventKind::Remove(_) => {
+                if self.files.contains_key(&observation) {
+                    self.update(observation, path)?;
+                    self.close(observation)?;
                 }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the open waterfall files for replacement and staleness and opens
+    /// waterfall files that are still being written
+    fn poll(&mut self) -> Result<()> {
+        self.last_poll = Instant::now();
+
+        let observations = self.files.keys().cloned().collect::<Vec<_>>();
+        for observation in observations {
+            let file = self.files.get_mut(&observation).unwrap();
+            let replaced = match file.is_replaced() {
+                Ok(replaced) => replaced,
                 Err(err) => {
-                    log::error!(
-                        "Failed to receive waterfall watcher event: {}. Stopping watcher.",
-                        err
-                    );
-                    break;
+                    log::error!("Failed to check waterfall file: {}", err);
+                    true
+                }
+            };
+
+            if replaced {
+                log::warn!(
+                    "Waterfall file of observation {} was truncated or replaced",
+                    observation
+                );
+                let path = file.path.clone();
+                self.close(observation)?;
+                // read the new file from the start
+                self.finished.remove(&path);
+            } else if file.last_row.elapsed() >= STALE_AFTER {
+                self.close(observation)?;
+            }
+        }
+
+        let entries = match std::fs::read_dir(&self.path) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::error!("Failed to scan {}: {}", self.path.display(), err);
+                return Ok(());
+            }
+        };
+
+        // forget the finished files which were moved away
+        self.finished.retain(|path| path.exists());
+
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(err) => {
+                    log::error!("Failed to scan {}: {}", self.path.display(), err);
+                    continue;
                 }
+            };
+            let observation = match observation_id(&path) {
+                Some(observation) => observation,
+                None => continue,
+            };
+
+            // only waterfalls which are still being written
+            let modified = path.metadata().and_then(|metadata| metadata.modified());
+            match modified.map(|modified| modified.elapsed()) {
+                Ok(Ok(elapsed)) if elapsed >= STALE_AFTER => continue,
+                Err(_) => continue,
+                _ => {}
             }
+
+            self.update(observation, &path)?;
         }
 
         Ok(())
     }
 
-    fn on_waterfall_file_created(
-        &mut self,
-        observation: u64,
-        path: &std::path::PathBuf,
-    ) -> Result<()> {
-        match OpenOptions::new().read(true).open(&path) {
-            Ok(file) => {
-                // wait until the header is written
-                // if it takes longer than 5 seconds we stop and handle the next
-                // event, this also means we're skipping this waterfall
-                let now = std::time::SystemTime::now();
-                while file.metadata().unwrap().len() < HEADER_SIZE {
-                    match now.elapsed() {
-                        Ok(elapsed) if elapsed.as_secs() <= 5 => {
-                            thread::sleep(std::time::Duration::from_millis(10))
-                        }
-                        _ => bail!("Missing Waterfall Header"),
-                    };
-                }
+    /// Opens the waterfall file if necessary and sends its new rows to the ui
+    fn update(&mut self, observation: u64, path: &Path) -> Result<()> {
+        if !self.files.contains_key(&observation) {
+            if self.invalid.contains(path) || self.finished.contains(path) {
+                return Ok(());
+            }
 
-                let mut reader = BufReader::new(file);
-                let header = WaterfallHeader::from_reader(&mut reader).unwrap();
-
-                let frequencies: Vec<_> = linspace::<f32>(
-                    -0.5 * header.sample_rate as f32,
-                    0.5 * header.sample_rate as f32,
-                    header.fft_size as usize,
-                )
-                .collect();
-
-                if let Err(err) = self
-                    .event_tx
-                    .send(Event::WaterfallCreated(observation, frequencies))
-                {
-                    log::error!("Failed to send waterfall creation event: {}", err);
+            match WaterfallFile::open(path, self.max_rows) {
+                Ok(Some((file, header))) => {
+                    log::info!("Opened waterfall file for observation {}", observation);
+                    self.files.insert(observation, file);
+                    self.event_tx.send(Event::WaterfallCreated(
+                        observation,
+                        self.station,
+                        header.center_freq,
+                        header.frequencies(),
+                    ))?;
                 }
+                // wait until the header is written
+                Ok(None) => return Ok(()),
+                // the file might have been moved by the satnogs-client in the meantime
+                Err(err) => {
+                    log::error!(
+                        "Failed to open waterfall file {}: {:#}",
+                        path.display(),
+                        err
+                    );
+                    self.invalid.insert(path.to_path_buf());
+                    return Ok(());
+                }
+            }
+        }
 
-                self.file = Some(WaterfallFile {
-                    fft_size: header.fft_size as u64,
-                    _observation: observation,
-                    reader,
-                });
+        let file = self.files.get_mut(&observation).unwrap();
+        // an older file of the same observation, e.g. after the file was replaced
+        if file.path != path {
+            return Ok(());
+        }
 
-                Ok(())
+        match file.read_rows() {
+            Ok(rows) => {
+                for (seconds, power) in rows {
+                    self.event_tx
+                        .send(Event::WaterfallData(observation, seconds, power))?;
+                }
             }
             Err(err) => {
                 log::error!(
-                    "Failed to open waterfall file {}: {}",
-                    path.to_str().unwrap_or(""),
+                    "Failed to read waterfall file {}: {:#}",
+                    path.display(),
                     err
                 );
-                Err(err.into())
+                self.close(observation)?;
             }
         }
-    }
-
-    fn on_waterfall_data_changed(&mut self) -> Result<()> {
-        while self.is_data_available() {
-            if let Some(file) = self.file.as_mut() {
-                let seconds = file
-                    .reader
-                    .read_i64::<LittleEndian>()?;
-                let mut power = vec![];
-                power.reserve(file.fft_size as usize);
 
-                for _ in 0..file.fft_size {
-                    power.push(
-                        file.reader
-                            .read_f32::<LittleEndian>()?,
-                    );
-                }
+        Ok(())
+    }
 
-                self.event_tx
-                    .send(Event::WaterfallData(seconds, power))?;
-            }
+    fn close(&mut self, observation: u64) -> Result<()> {
+        if let Some(file) = self.files.remove(&observation) {
+            self.finished.insert(file.path);
+            log::info!("Closed waterfall file for observation {}", observation);
+            self.event_tx.send(Event::WaterfallClosed(observation))?;
         }
 
         Ok(())
     }
+}
 
-    fn on_waterfall_closed(&mut self, observation: u64) -> Result<()> {
-        log::info!("Closed waterfall file for observation {}", observation);
-        self.file = None;
-        self.event_tx
-            .send(Event::WaterfallClosed(observation))
-            .map_err(|e| e.into())
-    }
+/// Observation of a waterfall file written by the satnogs-client
+fn observation_id(path: &Path) -> Option<u64> {
+    RE.captures(path.to_str().unwrap_or(""))
+        .and_then(|captures| captures[1].parse().ok())
+}
 
-    fn is_data_available(&mut self) -> bool {
-        if let Some(file) = self.file.as_mut() {
-            let size = file.reader.get_ref().metadata().unwrap().len();
-            let position = file.reader.seek(SeekFrom::Current(0)).unwrap();
+/// Reads one spectrum row, the timestamp followed by the power of each FFT bin
+fn read_row<E: ByteOrder, T: Read>(reader: &mut T, fft_size: u64) -> Result<(i64, Vec<f32>)> {
+    let seconds = reader.read_i64::<E>()?;
+    let mut power = Vec::with_capacity(fft_size as usize);
 
-            size - position >= file.fft_size * 4 + 8
-        } else {
-            false
-        }
+    for _ in 0..fft_size {
+        power.push(reader.read_f32::<E>()?);
     }
+
+    Ok((seconds, power))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn waterfall_buffer_decimates_old_rows() {
+        let mut buffer = WaterfallBuffer::new(8);
+        for timestamp in 0..9 {
+            buffer.push((timestamp, vec![timestamp as f32]));
+        }
+
+        let timestamps = buffer.rows().iter().map(|row| row.0).collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![0, 2, 4, 5, 6, 7, 8]);
+        assert_eq!(buffer.rows()[0].1, vec![1.0]);
+
+        for timestamp in 9..1000 {
+            buffer.push((timestamp, vec![timestamp as f32]));
+        }
+        assert!(buffer.len() <= 8);
+        assert_eq!(buffer.rows()[0].0, 0);
+        assert_eq!(buffer.rows()[buffer.len() - 1].0, 999);
+    }
+
     #[test]
     fn can_parse_datetime_string() {
         let buf: &[u8] = b"2020-03-23T09:34:47.193416Z\x00\xde\xad\xc0\xde";
@@ -281,4 +713,177 @@ mod tests {
         let buf: &[u8] = b"2020-03!23T09:34:47.193416Z\x00\xde\xad\xc0\xde";
         let _datetime = parse_timestamp(buf).unwrap();
     }
+
+    fn header<E: ByteOrder>(fft_size: u32, sample_rate: u32, endianess: u32) -> Vec<u8> {
+        let mut header = b"2020-03-23T09:34:47.193416Z\x00\x00\x00\x00\x00".to_vec();
+        let mut fields = [0; 20];
+        E::write_u32(&mut fields[0..], fft_size);
+        E::write_u32(&mut fields[4..], sample_rate);
+        E::write_u32(&mut fields[8..], 10);
+        E::write_f32(&mut fields[12..], 437_500_000.0);
+        E::write_u32(&mut fields[16..], endianess);
+        header.extend_from_slice(&fields);
+
+        header
+    }
+
+    #[test]
+    fn can_parse_header_in_network_byte_order() {
+        let buf = header::<BigEndian>(1024, 48_000, 1);
+        let header = WaterfallHeader::from_reader(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(header.fft_size, 1024);
+        assert_eq!(header.sample_rate, 48_000);
+        assert_eq!(header.endianess, Endianness::Big);
+    }
+
+    #[test]
+    fn can_parse_header_in_host_byte_order() {
+        let buf = header::<LittleEndian>(1024, 48_000, 1);
+        let header = WaterfallHeader::from_reader(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(header.fft_size, 1024);
+        assert_eq!(header.nfft_per_row, 10);
+        assert_eq!(header.endianess, Endianness::Big);
+    }
+
+    #[test]
+    fn can_parse_header_in_little_endian_host_byte_order() {
+        // the flag 0 reads the same in both byte orders
+        let buf = header::<LittleEndian>(1024, 48_000, 0);
+        let header = WaterfallHeader::from_reader(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(header.fft_size, 1024);
+        assert_eq!(header.sample_rate, 48_000);
+        assert_eq!(header.endianess, Endianness::Little);
+    }
+
+    #[test]
+    fn can_read_recording_with_incomplete_row() {
+        let mut buf = header::<BigEndian>(4, 48_000, 0);
+        for row in 0..3 {
+            buf.extend_from_slice(&(row as i64 * 100_000).to_le_bytes());
+            for _ in 0..4 {
+                buf.extend_from_slice(&(-50.0f32).to_le_bytes());
+            }
+        }
+        buf.truncate(buf.len() - 6);
+
+        let path = std::env::temp_dir().join("receiving_waterfall_1234_2020-03-23T09-34-47.dat");
+        std::fs::write(&path, &buf).unwrap();
+        let recording = WaterfallRecording::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(recording.observation, Some(1234));
+        assert_eq!(recording.frequencies.len(), 4);
+        assert_eq!(recording.rows.len(), 2);
+        assert_eq!(recording.rows[1], (100_000, vec![-50.0; 4]));
+    }
+
+    fn row(timestamp: i64) -> Vec<u8> {
+        let mut buf = timestamp.to_le_bytes().to_vec();
+        for _ in 0..4 {
+            buf.extend_from_slice(&(-50.0f32).to_le_bytes());
+        }
+
+        buf
+    }
+
+    #[test]
+    fn resumes_at_row_boundary() {
+        // the header, 10 rows of 4 bins and an incomplete row
+        let size = HEADER_SIZE + 10 * 24 + 12;
+
+        assert_eq!(resume_position(size, 4, 100), HEADER_SIZE);
+        assert_eq!(resume_position(size, 4, 3), HEADER_SIZE + 7 * 24);
+        assert_eq!(resume_position(10, 4, 3), HEADER_SIZE);
+    }
+
+    #[test]
+    fn watcher_picks_up_waterfall_in_progress() {
+        let dir = std::env::temp_dir().join(format!("satnogs-monitor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("receiving_waterfall_4321_2020-03-23T09-34-47.dat");
+
+        let mut buf = header::<BigEndian>(4, 48_000, 0);
+        for timestamp in 0..3 {
+            buf.extend_from_slice(&row(timestamp));
+        }
+        std::fs::write(&path, &buf).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::sync_channel(100);
+        let mut watcher = WaterfallWatcher::new(dir.to_str().unwrap(), Some(7), 2, tx).unwrap();
+        watcher.poll().unwrap();
+
+        match rx.try_recv().unwrap() {
+            Event::WaterfallCreated(4321, Some(7), _, frequencies) => {
+                assert_eq!(frequencies.len(), 4)
+            }
+            _ => panic!("expected the waterfall to be created"),
+        }
+        // only the most recent rows of a waterfall in progress are read
+        let timestamps = rx
+            .try_iter()
+            .map(|event| match event {
+                Event::WaterfallData(4321, timestamp, _) => timestamp,
+                _ => panic!("expected waterfall rows"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![1, 2]);
+
+        // a truncated file is closed and read again from the start
+        let mut buf = header::<BigEndian>(4, 48_000, 0);
+        buf.extend_from_slice(&row(5));
+        std::fs::write(&path, &buf).unwrap();
+        watcher.poll().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let events = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], Event::WaterfallClosed(4321)));
+        assert!(matches!(events[1], Event::WaterfallCreated(4321, ..)));
+        assert!(matches!(events[2], Event::WaterfallData(4321, 5, _)));
+    }
+
+    #[test]
+    fn watcher_does_not_reopen_closed_waterfall() {
+        use notify::event::{AccessKind, AccessMode, EventKind};
+
+        let dir =
+            std::env::temp_dir().join(format!("satnogs-monitor-closed-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("receiving_waterfall_4322_2020-03-23T09-34-47.dat");
+
+        let mut buf = header::<BigEndian>(4, 48_000, 0);
+        buf.extend_from_slice(&row(0));
+        std::fs::write(&path, &buf).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::sync_channel(100);
+        let mut watcher = WaterfallWatcher::new(dir.to_str().unwrap(), None, 10, tx).unwrap();
+        watcher.poll().unwrap();
+        watcher
+            .on_file_event(
+                EventKind::Access(AccessKind::Close(AccessMode::Write)),
+                &path,
+            )
+            .unwrap();
+        // the file is still recent but the observation is over
+        watcher.poll().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let events = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], Event::WaterfallCreated(4322, ..)));
+        assert!(matches!(events[1], Event::WaterfallData(4322, 0, _)));
+        assert!(matches!(events[2], Event::WaterfallClosed(4322)));
+    }
+
+    #[test]
+    fn err_on_header_out_of_bounds() {
+        let buf = header::<BigEndian>(0, 48_000, 0);
+        assert!(WaterfallHeader::from_reader(&mut buf.as_slice()).is_err());
+
+        let buf = header::<BigEndian>(1024, 0, 0);
+        assert!(WaterfallHeader::from_reader(&mut buf.as_slice()).is_err());
+    }
 }
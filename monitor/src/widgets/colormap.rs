@@ -0,0 +1,207 @@
+use lazy_static::lazy_static;
+use serde_derive::Deserialize;
+use tui::style::Color;
+
+use super::inferno::INFERNO;
+use super::magma::MAGMA;
+use super::plasma::PLASMA;
+use super::viridis::VIRIDIS;
+
+lazy_static! {
+    /// The 24 step gray ramp of 256 color terminals
+    static ref GRAYSCALE: Vec<Color> = (0..256)
+        .map(|index| Color::Indexed(232 + (index * 24 / 256) as u8))
+        .collect();
+}
+
+/// Color ramp used for all colormaps except grayscale on 16 color terminals
+const RAMP_16: [Color; 8] = [
+    Color::Black,
+    Color::Blue,
+    Color::Magenta,
+    Color::Red,
+    Color::LightRed,
+    Color::Yellow,
+    Color::LightYellow,
+    Color::White,
+];
+
+const GRAY_RAMP_16: [Color; 4] = [Color::Black, Color::DarkGray, Color::Gray, Color::White];
+
+/// Intensities of the 6 x 6 x 6 color cube of the xterm 256 color palette
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// Colors the terminal is able to display
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum ColorMode {
+    /// Detect the color support of the terminal at startup
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+impl ColorMode {
+    /// Guesses the color support from `COLORTERM`, the terminfo database and `TERM`
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorMode::TrueColor;
+        }
+
+        let colors = std::process::Command::new("tput")
+            .arg("colors")
+            .output()
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .and_then(|colors| colors.trim().parse::<u32>().ok());
+
+        match colors {
+            Some(colors) if colors >= 256 => ColorMode::Ansi256,
+            Some(_) => ColorMode::Ansi16,
+            None if std::env::var("TERM")
+                .unwrap_or_default()
+                .contains("256color") =>
+            {
+                ColorMode::Ansi256
+            }
+            None => ColorMode::Ansi16,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ColorMode::Auto => "auto",
+            ColorMode::TrueColor => "truecolor",
+            ColorMode::Ansi256 => "256",
+            ColorMode::Ansi16 => "16",
+        }
+    }
+}
+
+/// Colors used to map the signal power in the waterfall
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Inferno,
+    Plasma,
+    Grayscale,
+}
+
+impl Colormap {
+    pub fn name(&self) -> &str {
+        match self {
+            Colormap::Viridis => "viridis",
+            Colormap::Magma => "magma",
+            Colormap::Inferno => "inferno",
+            Colormap::Plasma => "plasma",
+            Colormap::Grayscale => "grayscale",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Colormap::Viridis => Colormap::Magma,
+            Colormap::Magma => Colormap::Inferno,
+            Colormap::Inferno => Colormap::Plasma,
+            Colormap::Plasma => Colormap::Grayscale,
+            Colormap::Grayscale => Colormap::Viridis,
+        }
+    }
+
+    /// The 256 colors of the map from low to high power
+    pub fn colors(&self) -> &'static [Color] {
+        match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Magma => &MAGMA,
+            Colormap::Inferno => &INFERNO,
+            Colormap::Plasma => &PLASMA,
+            Colormap::Grayscale => &GRAYSCALE,
+        }
+    }
+
+    /// The 256 colors of the map reduced to the colors the terminal can display
+    pub fn palette(&self, mode: ColorMode) -> Vec<Color> {
+        match mode {
+            ColorMode::Auto | ColorMode::TrueColor => self.colors().to_vec(),
+            ColorMode::Ansi256 => self
+                .colors()
+                .iter()
+                .map(|color| xterm_256(*color))
+                .collect(),
+            ColorMode::Ansi16 => {
+                let ramp: &[Color] = match self {
+                    Colormap::Grayscale => &GRAY_RAMP_16,
+                    _ => &RAMP_16,
+                };
+
+                (0..256)
+                    .map(|index| ramp[index * ramp.len() / 256])
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Finds the closest color of the xterm 256 color cube or gray ramp
+fn xterm_256(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        color => return color,
+    };
+
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let dr = r as i32 - r2 as i32;
+        let dg = g as i32 - g2 as i32;
+        let db = b as i32 - b2 as i32;
+        dr * dr + dg * dg + db * db
+    };
+
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|index| (CUBE_LEVELS[*index] as i32 - value as i32).abs())
+            .unwrap()
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    let gray_index = ((r as u32 + g as u32 + b as u32) / 3).saturating_sub(3) / 10;
+    let gray_index = gray_index.min(23) as u8;
+    let gray = 8 + 10 * gray_index;
+
+    if distance((gray, gray, gray)) < distance(cube) {
+        Color::Indexed(232 + gray_index)
+    } else {
+        Color::Indexed(16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantizes_to_xterm_palette() {
+        assert_eq!(xterm_256(Color::Rgb(0xff, 0x00, 0x00)), Color::Indexed(196));
+        assert_eq!(xterm_256(Color::Rgb(0x44, 0x01, 0x54)), Color::Indexed(53));
+        assert_eq!(xterm_256(Color::Rgb(0x80, 0x80, 0x80)), Color::Indexed(244));
+        assert_eq!(xterm_256(Color::Indexed(7)), Color::Indexed(7));
+    }
+
+    #[test]
+    fn palettes_keep_all_levels() {
+        for mode in &[ColorMode::TrueColor, ColorMode::Ansi256, ColorMode::Ansi16] {
+            assert_eq!(Colormap::Magma.palette(*mode).len(), 256);
+        }
+
+        let palette = Colormap::Viridis.palette(ColorMode::Ansi16);
+        assert_eq!(palette[0], Color::Black);
+        assert_eq!(palette[255], Color::White);
+    }
+}
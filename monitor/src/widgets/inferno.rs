@@ -0,0 +1,264 @@
+use lazy_static::lazy_static;
+use tui::style::Color;
+
+// the matplotlib inferno colormap
+lazy_static! {
+    pub static ref INFERNO: Vec<Color> = vec![
+        Color::Rgb(0x00, 0x00, 0x04),
+        Color::Rgb(0x01, 0x00, 0x05),
+        Color::Rgb(0x01, 0x01, 0x06),
+        Color::Rgb(0x01, 0x01, 0x08),
+        Color::Rgb(0x02, 0x01, 0x0A),
+        Color::Rgb(0x02, 0x02, 0x0C),
+        Color::Rgb(0x02, 0x02, 0x0E),
+        Color::Rgb(0x03, 0x02, 0x10),
+        Color::Rgb(0x04, 0x03, 0x12),
+        Color::Rgb(0x04, 0x03, 0x14),
+        Color::Rgb(0x05, 0x04, 0x17),
+        Color::Rgb(0x06, 0x04, 0x19),
+        Color::Rgb(0x07, 0x05, 0x1B),
+        Color::Rgb(0x08, 0x05, 0x1D),
+        Color::Rgb(0x09, 0x06, 0x1F),
+        Color::Rgb(0x0A, 0x07, 0x22),
+        Color::Rgb(0x0B, 0x07, 0x24),
+        Color::Rgb(0x0C, 0x08, 0x26),
+        Color::Rgb(0x0D, 0x08, 0x29),
+        Color::Rgb(0x0E, 0x09, 0x2B),
+        Color::Rgb(0x10, 0x09, 0x2D),
+        Color::Rgb(0x11, 0x0A, 0x30),
+        Color::Rgb(0x12, 0x0A, 0x32),
+        Color::Rgb(0x14, 0x0B, 0x34),
+        Color::Rgb(0x15, 0x0B, 0x37),
+        Color::Rgb(0x16, 0x0B, 0x39),
+        Color::Rgb(0x18, 0x0C, 0x3C),
+        Color::Rgb(0x19, 0x0C, 0x3E),
+        Color::Rgb(0x1B, 0x0C, 0x41),
+        Color::Rgb(0x1C, 0x0C, 0x43),
+        Color::Rgb(0x1E, 0x0C, 0x45),
+        Color::Rgb(0x1F, 0x0C, 0x48),
+        Color::Rgb(0x21, 0x0C, 0x4A),
+        Color::Rgb(0x23, 0x0C, 0x4C),
+        Color::Rgb(0x24, 0x0C, 0x4F),
+        Color::Rgb(0x26, 0x0C, 0x51),
+        Color::Rgb(0x28, 0x0B, 0x53),
+        Color::Rgb(0x29, 0x0B, 0x55),
+        Color::Rgb(0x2B, 0x0B, 0x57),
+        Color::Rgb(0x2D, 0x0B, 0x59),
+        Color::Rgb(0x2F, 0x0A, 0x5B),
+        Color::Rgb(0x31, 0x0A, 0x5C),
+        Color::Rgb(0x32, 0x0A, 0x5E),
+        Color::Rgb(0x34, 0x0A, 0x5F),
+        Color::Rgb(0x36, 0x09, 0x61),
+        Color::Rgb(0x38, 0x09, 0x62),
+        Color::Rgb(0x39, 0x09, 0x63),
+        Color::Rgb(0x3B, 0x09, 0x64),
+        Color::Rgb(0x3D, 0x09, 0x65),
+        Color::Rgb(0x3E, 0x09, 0x66),
+        Color::Rgb(0x40, 0x0A, 0x67),
+        Color::Rgb(0x42, 0x0A, 0x68),
+        Color::Rgb(0x44, 0x0A, 0x68),
+        Color::Rgb(0x45, 0x0A, 0x69),
+        Color::Rgb(0x47, 0x0B, 0x6A),
+        Color::Rgb(0x49, 0x0B, 0x6A),
+        Color::Rgb(0x4A, 0x0C, 0x6B),
+        Color::Rgb(0x4C, 0x0C, 0x6B),
+        Color::Rgb(0x4D, 0x0D, 0x6C),
+        Color::Rgb(0x4F, 0x0D, 0x6C),
+        Color::Rgb(0x51, 0x0E, 0x6C),
+        Color::Rgb(0x52, 0x0E, 0x6D),
+        Color::Rgb(0x54, 0x0F, 0x6D),
+        Color::Rgb(0x55, 0x0F, 0x6D),
+        Color::Rgb(0x57, 0x10, 0x6E),
+        Color::Rgb(0x59, 0x10, 0x6E),
+        Color::Rgb(0x5A, 0x11, 0x6E),
+        Color::Rgb(0x5C, 0x12, 0x6E),
+        Color::Rgb(0x5D, 0x12, 0x6E),
+        Color::Rgb(0x5F, 0x13, 0x6E),
+        Color::Rgb(0x61, 0x13, 0x6E),
+        Color::Rgb(0x62, 0x14, 0x6E),
+        Color::Rgb(0x64, 0x15, 0x6E),
+        Color::Rgb(0x65, 0x15, 0x6E),
+        Color::Rgb(0x67, 0x16, 0x6E),
+        Color::Rgb(0x69, 0x16, 0x6E),
+        Color::Rgb(0x6A, 0x17, 0x6E),
+        Color::Rgb(0x6C, 0x18, 0x6E),
+        Color::Rgb(0x6D, 0x18, 0x6E),
+        Color::Rgb(0x6F, 0x19, 0x6E),
+        Color::Rgb(0x71, 0x19, 0x6E),
+        Color::Rgb(0x72, 0x1A, 0x6E),
+        Color::Rgb(0x74, 0x1A, 0x6E),
+        Color::Rgb(0x75, 0x1B, 0x6E),
+        Color::Rgb(0x77, 0x1C, 0x6D),
+        Color::Rgb(0x78, 0x1C, 0x6D),
+        Color::Rgb(0x7A, 0x1D, 0x6D),
+        Color::Rgb(0x7C, 0x1D, 0x6D),
+        Color::Rgb(0x7D, 0x1E, 0x6D),
+        Color::Rgb(0x7F, 0x1E, 0x6C),
+        Color::Rgb(0x80, 0x1F, 0x6C),
+        Color::Rgb(0x82, 0x20, 0x6C),
+        Color::Rgb(0x84, 0x20, 0x6B),
+        Color::Rgb(0x85, 0x21, 0x6B),
+        Color::Rgb(0x87, 0x21, 0x6B),
+        Color::Rgb(0x88, 0x22, 0x6A),
+        Color::Rgb(0x8A, 0x22, 0x6A),
+        Color::Rgb(0x8C, 0x23, 0x69),
+        Color::Rgb(0x8D, 0x23, 0x69),
+        Color::Rgb(0x8F, 0x24, 0x69),
+        Color::Rgb(0x90, 0x25, 0x68),
+        Color::Rgb(0x92, 0x25, 0x68),
+        Color::Rgb(0x93, 0x26, 0x67),
+        Color::Rgb(0x95, 0x26, 0x67),
+        Color::Rgb(0x97, 0x27, 0x66),
+        Color::Rgb(0x98, 0x27, 0x66),
+        Color::Rgb(0x9A, 0x28, 0x65),
+        Color::Rgb(0x9B, 0x29, 0x64),
+        Color::Rgb(0x9D, 0x29, 0x64),
+        Color::Rgb(0x9F, 0x2A, 0x63),
+        Color::Rgb(0xA0, 0x2A, 0x63),
+        Color::Rgb(0xA2, 0x2B, 0x62),
+        Color::Rgb(0xA3, 0x2C, 0x61),
+        Color::Rgb(0xA5, 0x2C, 0x60),
+        Color::Rgb(0xA6, 0x2D, 0x60),
+        Color::Rgb(0xA8, 0x2E, 0x5F),
+        Color::Rgb(0xA9, 0x2E, 0x5E),
+        Color::Rgb(0xAB, 0x2F, 0x5E),
+        Color::Rgb(0xAD, 0x30, 0x5D),
+        Color::Rgb(0xAE, 0x30, 0x5C),
+        Color::Rgb(0xB0, 0x31, 0x5B),
+        Color::Rgb(0xB1, 0x32, 0x5A),
+        Color::Rgb(0xB3, 0x32, 0x5A),
+        Color::Rgb(0xB4, 0x33, 0x59),
+        Color::Rgb(0xB6, 0x34, 0x58),
+        Color::Rgb(0xB7, 0x35, 0x57),
+        Color::Rgb(0xB9, 0x35, 0x56),
+        Color::Rgb(0xBA, 0x36, 0x55),
+        Color::Rgb(0xBC, 0x37, 0x54),
+        Color::Rgb(0xBD, 0x38, 0x53),
+        Color::Rgb(0xBF, 0x39, 0x52),
+        Color::Rgb(0xC0, 0x3A, 0x51),
+        Color::Rgb(0xC1, 0x3A, 0x50),
+        Color::Rgb(0xC3, 0x3B, 0x4F),
+        Color::Rgb(0xC4, 0x3C, 0x4E),
+        Color::Rgb(0xC6, 0x3D, 0x4D),
+        Color::Rgb(0xC7, 0x3E, 0x4C),
+        Color::Rgb(0xC8, 0x3F, 0x4B),
+        Color::Rgb(0xCA, 0x40, 0x4A),
+        Color::Rgb(0xCB, 0x41, 0x49),
+        Color::Rgb(0xCC, 0x42, 0x48),
+        Color::Rgb(0xCE, 0x43, 0x47),
+        Color::Rgb(0xCF, 0x44, 0x46),
+        Color::Rgb(0xD0, 0x45, 0x45),
+        Color::Rgb(0xD2, 0x46, 0x44),
+        Color::Rgb(0xD3, 0x47, 0x43),
+        Color::Rgb(0xD4, 0x48, 0x42),
+        Color::Rgb(0xD5, 0x4A, 0x41),
+        Color::Rgb(0xD7, 0x4B, 0x3F),
+        Color::Rgb(0xD8, 0x4C, 0x3E),
+        Color::Rgb(0xD9, 0x4D, 0x3D),
+        Color::Rgb(0xDA, 0x4E, 0x3C),
+        Color::Rgb(0xDB, 0x50, 0x3B),
+        Color::Rgb(0xDD, 0x51, 0x3A),
+        Color::Rgb(0xDE, 0x52, 0x38),
+        Color::Rgb(0xDF, 0x53, 0x37),
+        Color::Rgb(0xE0, 0x55, 0x36),
+        Color::Rgb(0xE1, 0x56, 0x35),
+        Color::Rgb(0xE2, 0x57, 0x34),
+        Color::Rgb(0xE3, 0x59, 0x33),
+        Color::Rgb(0xE4, 0x5A, 0x31),
+        Color::Rgb(0xE5, 0x5C, 0x30),
+        Color::Rgb(0xE6, 0x5D, 0x2F),
+        Color::Rgb(0xE7, 0x5E, 0x2E),
+        Color::Rgb(0xE8, 0x60, 0x2D),
+        Color::Rgb(0xE9, 0x61, 0x2B),
+        Color::Rgb(0xEA, 0x63, 0x2A),
+        Color::Rgb(0xEB, 0x64, 0x29),
+        Color::Rgb(0xEB, 0x66, 0x28),
+        Color::Rgb(0xEC, 0x67, 0x26),
+        Color::Rgb(0xED, 0x69, 0x25),
+        Color::Rgb(0xEE, 0x6A, 0x24),
+        Color::Rgb(0xEF, 0x6C, 0x23),
+        Color::Rgb(0xEF, 0x6E, 0x21),
+        Color::Rgb(0xF0, 0x6F, 0x20),
+        Color::Rgb(0xF1, 0x71, 0x1F),
+        Color::Rgb(0xF1, 0x73, 0x1D),
+        Color::Rgb(0xF2, 0x74, 0x1C),
+        Color::Rgb(0xF3, 0x76, 0x1B),
+        Color::Rgb(0xF3, 0x78, 0x19),
+        Color::Rgb(0xF4, 0x79, 0x18),
+        Color::Rgb(0xF5, 0x7B, 0x17),
+        Color::Rgb(0xF5, 0x7D, 0x15),
+        Color::Rgb(0xF6, 0x7E, 0x14),
+        Color::Rgb(0xF6, 0x80, 0x13),
+        Color::Rgb(0xF7, 0x82, 0x12),
+        Color::Rgb(0xF7, 0x84, 0x10),
+        Color::Rgb(0xF8, 0x85, 0x0F),
+        Color::Rgb(0xF8, 0x87, 0x0E),
+        Color::Rgb(0xF8, 0x89, 0x0C),
+        Color::Rgb(0xF9, 0x8B, 0x0B),
+        Color::Rgb(0xF9, 0x8C, 0x0A),
+        Color::Rgb(0xF9, 0x8E, 0x09),
+        Color::Rgb(0xFA, 0x90, 0x08),
+        Color::Rgb(0xFA, 0x92, 0x07),
+        Color::Rgb(0xFA, 0x94, 0x07),
+        Color::Rgb(0xFB, 0x96, 0x06),
+        Color::Rgb(0xFB, 0x97, 0x06),
+        Color::Rgb(0xFB, 0x99, 0x06),
+        Color::Rgb(0xFB, 0x9B, 0x06),
+        Color::Rgb(0xFB, 0x9D, 0x07),
+        Color::Rgb(0xFC, 0x9F, 0x07),
+        Color::Rgb(0xFC, 0xA1, 0x08),
+        Color::Rgb(0xFC, 0xA3, 0x09),
+        Color::Rgb(0xFC, 0xA5, 0x0A),
+        Color::Rgb(0xFC, 0xA6, 0x0C),
+        Color::Rgb(0xFC, 0xA8, 0x0D),
+        Color::Rgb(0xFC, 0xAA, 0x0F),
+        Color::Rgb(0xFC, 0xAC, 0x11),
+        Color::Rgb(0xFC, 0xAE, 0x12),
+        Color::Rgb(0xFC, 0xB0, 0x14),
+        Color::Rgb(0xFC, 0xB2, 0x16),
+        Color::Rgb(0xFC, 0xB4, 0x18),
+        Color::Rgb(0xFB, 0xB6, 0x1A),
+        Color::Rgb(0xFB, 0xB8, 0x1D),
+        Color::Rgb(0xFB, 0xBA, 0x1F),
+        Color::Rgb(0xFB, 0xBC, 0x21),
+        Color::Rgb(0xFB, 0xBE, 0x23),
+        Color::Rgb(0xFA, 0xC0, 0x26),
+        Color::Rgb(0xFA, 0xC2, 0x28),
+        Color::Rgb(0xFA, 0xC4, 0x2A),
+        Color::Rgb(0xFA, 0xC6, 0x2D),
+        Color::Rgb(0xF9, 0xC7, 0x2F),
+        Color::Rgb(0xF9, 0xC9, 0x32),
+        Color::Rgb(0xF9, 0xCB, 0x35),
+        Color::Rgb(0xF8, 0xCD, 0x37),
+        Color::Rgb(0xF8, 0xCF, 0x3A),
+        Color::Rgb(0xF7, 0xD1, 0x3D),
+        Color::Rgb(0xF7, 0xD3, 0x40),
+        Color::Rgb(0xF6, 0xD5, 0x43),
+        Color::Rgb(0xF6, 0xD7, 0x46),
+        Color::Rgb(0xF5, 0xD9, 0x49),
+        Color::Rgb(0xF5, 0xDB, 0x4C),
+        Color::Rgb(0xF4, 0xDD, 0x4F),
+        Color::Rgb(0xF4, 0xDF, 0x53),
+        Color::Rgb(0xF4, 0xE1, 0x56),
+        Color::Rgb(0xF3, 0xE3, 0x5A),
+        Color::Rgb(0xF3, 0xE5, 0x5D),
+        Color::Rgb(0xF2, 0xE6, 0x61),
+        Color::Rgb(0xF2, 0xE8, 0x65),
+        Color::Rgb(0xF2, 0xEA, 0x69),
+        Color::Rgb(0xF1, 0xEC, 0x6D),
+        Color::Rgb(0xF1, 0xED, 0x71),
+        Color::Rgb(0xF1, 0xEF, 0x75),
+        Color::Rgb(0xF1, 0xF1, 0x79),
+        Color::Rgb(0xF2, 0xF2, 0x7D),
+        Color::Rgb(0xF2, 0xF4, 0x82),
+        Color::Rgb(0xF3, 0xF5, 0x86),
+        Color::Rgb(0xF3, 0xF6, 0x8A),
+        Color::Rgb(0xF4, 0xF8, 0x8E),
+        Color::Rgb(0xF5, 0xF9, 0x92),
+        Color::Rgb(0xF6, 0xFA, 0x96),
+        Color::Rgb(0xF8, 0xFB, 0x9A),
+        Color::Rgb(0xF9, 0xFC, 0x9D),
+        Color::Rgb(0xFA, 0xFD, 0xA1),
+        Color::Rgb(0xFC, 0xFF, 0xA4)
+    ];
+}
@@ -0,0 +1,264 @@
+use lazy_static::lazy_static;
+use tui::style::Color;
+
+// the matplotlib magma colormap
+lazy_static! {
+    pub static ref MAGMA: Vec<Color> = vec![
+        Color::Rgb(0x00, 0x00, 0x04),
+        Color::Rgb(0x01, 0x00, 0x05),
+        Color::Rgb(0x01, 0x01, 0x06),
+        Color::Rgb(0x01, 0x01, 0x08),
+        Color::Rgb(0x02, 0x01, 0x09),
+        Color::Rgb(0x02, 0x02, 0x0B),
+        Color::Rgb(0x02, 0x02, 0x0D),
+        Color::Rgb(0x03, 0x03, 0x0F),
+        Color::Rgb(0x03, 0x03, 0x12),
+        Color::Rgb(0x04, 0x04, 0x14),
+        Color::Rgb(0x05, 0x04, 0x16),
+        Color::Rgb(0x06, 0x05, 0x18),
+        Color::Rgb(0x06, 0x05, 0x1A),
+        Color::Rgb(0x07, 0x06, 0x1C),
+        Color::Rgb(0x08, 0x07, 0x1E),
+        Color::Rgb(0x09, 0x07, 0x20),
+        Color::Rgb(0x0A, 0x08, 0x22),
+        Color::Rgb(0x0B, 0x09, 0x24),
+        Color::Rgb(0x0C, 0x09, 0x26),
+        Color::Rgb(0x0D, 0x0A, 0x29),
+        Color::Rgb(0x0E, 0x0B, 0x2B),
+        Color::Rgb(0x10, 0x0B, 0x2D),
+        Color::Rgb(0x11, 0x0C, 0x2F),
+        Color::Rgb(0x12, 0x0D, 0x31),
+        Color::Rgb(0x13, 0x0D, 0x34),
+        Color::Rgb(0x14, 0x0E, 0x36),
+        Color::Rgb(0x15, 0x0E, 0x38),
+        Color::Rgb(0x16, 0x0F, 0x3B),
+        Color::Rgb(0x18, 0x0F, 0x3D),
+        Color::Rgb(0x19, 0x10, 0x3F),
+        Color::Rgb(0x1A, 0x10, 0x42),
+        Color::Rgb(0x1C, 0x10, 0x44),
+        Color::Rgb(0x1D, 0x11, 0x47),
+        Color::Rgb(0x1E, 0x11, 0x49),
+        Color::Rgb(0x20, 0x11, 0x4B),
+        Color::Rgb(0x21, 0x11, 0x4E),
+        Color::Rgb(0x22, 0x11, 0x50),
+        Color::Rgb(0x24, 0x12, 0x53),
+        Color::Rgb(0x25, 0x12, 0x55),
+        Color::Rgb(0x27, 0x12, 0x58),
+        Color::Rgb(0x29, 0x11, 0x5A),
+        Color::Rgb(0x2A, 0x11, 0x5C),
+        Color::Rgb(0x2C, 0x11, 0x5F),
+        Color::Rgb(0x2D, 0x11, 0x61),
+        Color::Rgb(0x2F, 0x11, 0x63),
+        Color::Rgb(0x31, 0x11, 0x65),
+        Color::Rgb(0x33, 0x10, 0x67),
+        Color::Rgb(0x34, 0x10, 0x69),
+        Color::Rgb(0x36, 0x10, 0x6B),
+        Color::Rgb(0x38, 0x10, 0x6C),
+        Color::Rgb(0x39, 0x0F, 0x6E),
+        Color::Rgb(0x3B, 0x0F, 0x70),
+        Color::Rgb(0x3D, 0x0F, 0x71),
+        Color::Rgb(0x3F, 0x0F, 0x72),
+        Color::Rgb(0x40, 0x0F, 0x74),
+        Color::Rgb(0x42, 0x0F, 0x75),
+        Color::Rgb(0x44, 0x0F, 0x76),
+        Color::Rgb(0x45, 0x10, 0x77),
+        Color::Rgb(0x47, 0x10, 0x78),
+        Color::Rgb(0x49, 0x10, 0x78),
+        Color::Rgb(0x4A, 0x10, 0x79),
+        Color::Rgb(0x4C, 0x11, 0x7A),
+        Color::Rgb(0x4E, 0x11, 0x7B),
+        Color::Rgb(0x4F, 0x12, 0x7B),
+        Color::Rgb(0x51, 0x12, 0x7C),
+        Color::Rgb(0x52, 0x13, 0x7C),
+        Color::Rgb(0x54, 0x13, 0x7D),
+        Color::Rgb(0x56, 0x14, 0x7D),
+        Color::Rgb(0x57, 0x15, 0x7E),
+        Color::Rgb(0x59, 0x15, 0x7E),
+        Color::Rgb(0x5A, 0x16, 0x7E),
+        Color::Rgb(0x5C, 0x16, 0x7F),
+        Color::Rgb(0x5D, 0x17, 0x7F),
+        Color::Rgb(0x5F, 0x18, 0x7F),
+        Color::Rgb(0x60, 0x18, 0x80),
+        Color::Rgb(0x62, 0x19, 0x80),
+        Color::Rgb(0x64, 0x1A, 0x80),
+        Color::Rgb(0x65, 0x1A, 0x80),
+        Color::Rgb(0x67, 0x1B, 0x80),
+        Color::Rgb(0x68, 0x1C, 0x81),
+        Color::Rgb(0x6A, 0x1C, 0x81),
+        Color::Rgb(0x6B, 0x1D, 0x81),
+        Color::Rgb(0x6D, 0x1D, 0x81),
+        Color::Rgb(0x6E, 0x1E, 0x81),
+        Color::Rgb(0x70, 0x1F, 0x81),
+        Color::Rgb(0x72, 0x1F, 0x81),
+        Color::Rgb(0x73, 0x20, 0x81),
+        Color::Rgb(0x75, 0x21, 0x81),
+        Color::Rgb(0x76, 0x21, 0x81),
+        Color::Rgb(0x78, 0x22, 0x81),
+        Color::Rgb(0x79, 0x22, 0x82),
+        Color::Rgb(0x7B, 0x23, 0x82),
+        Color::Rgb(0x7C, 0x23, 0x82),
+        Color::Rgb(0x7E, 0x24, 0x82),
+        Color::Rgb(0x80, 0x25, 0x82),
+        Color::Rgb(0x81, 0x25, 0x81),
+        Color::Rgb(0x83, 0x26, 0x81),
+        Color::Rgb(0x84, 0x26, 0x81),
+        Color::Rgb(0x86, 0x27, 0x81),
+        Color::Rgb(0x88, 0x27, 0x81),
+        Color::Rgb(0x89, 0x28, 0x81),
+        Color::Rgb(0x8B, 0x29, 0x81),
+        Color::Rgb(0x8C, 0x29, 0x81),
+        Color::Rgb(0x8E, 0x2A, 0x81),
+        Color::Rgb(0x90, 0x2A, 0x81),
+        Color::Rgb(0x91, 0x2B, 0x81),
+        Color::Rgb(0x93, 0x2B, 0x80),
+        Color::Rgb(0x94, 0x2C, 0x80),
+        Color::Rgb(0x96, 0x2C, 0x80),
+        Color::Rgb(0x98, 0x2D, 0x80),
+        Color::Rgb(0x99, 0x2D, 0x80),
+        Color::Rgb(0x9B, 0x2E, 0x7F),
+        Color::Rgb(0x9C, 0x2E, 0x7F),
+        Color::Rgb(0x9E, 0x2F, 0x7F),
+        Color::Rgb(0xA0, 0x2F, 0x7F),
+        Color::Rgb(0xA1, 0x30, 0x7E),
+        Color::Rgb(0xA3, 0x30, 0x7E),
+        Color::Rgb(0xA5, 0x31, 0x7E),
+        Color::Rgb(0xA6, 0x31, 0x7D),
+        Color::Rgb(0xA8, 0x32, 0x7D),
+        Color::Rgb(0xAA, 0x33, 0x7D),
+        Color::Rgb(0xAB, 0x33, 0x7C),
+        Color::Rgb(0xAD, 0x34, 0x7C),
+        Color::Rgb(0xAE, 0x34, 0x7B),
+        Color::Rgb(0xB0, 0x35, 0x7B),
+        Color::Rgb(0xB2, 0x35, 0x7B),
+        Color::Rgb(0xB3, 0x36, 0x7A),
+        Color::Rgb(0xB5, 0x36, 0x7A),
+        Color::Rgb(0xB7, 0x37, 0x79),
+        Color::Rgb(0xB8, 0x37, 0x79),
+        Color::Rgb(0xBA, 0x38, 0x78),
+        Color::Rgb(0xBC, 0x39, 0x78),
+        Color::Rgb(0xBD, 0x39, 0x77),
+        Color::Rgb(0xBF, 0x3A, 0x77),
+        Color::Rgb(0xC0, 0x3A, 0x76),
+        Color::Rgb(0xC2, 0x3B, 0x75),
+        Color::Rgb(0xC4, 0x3C, 0x75),
+        Color::Rgb(0xC5, 0x3C, 0x74),
+        Color::Rgb(0xC7, 0x3D, 0x73),
+        Color::Rgb(0xC8, 0x3E, 0x73),
+        Color::Rgb(0xCA, 0x3E, 0x72),
+        Color::Rgb(0xCC, 0x3F, 0x71),
+        Color::Rgb(0xCD, 0x40, 0x71),
+        Color::Rgb(0xCF, 0x40, 0x70),
+        Color::Rgb(0xD0, 0x41, 0x6F),
+        Color::Rgb(0xD2, 0x42, 0x6F),
+        Color::Rgb(0xD3, 0x43, 0x6E),
+        Color::Rgb(0xD5, 0x44, 0x6D),
+        Color::Rgb(0xD6, 0x45, 0x6C),
+        Color::Rgb(0xD8, 0x45, 0x6C),
+        Color::Rgb(0xD9, 0x46, 0x6B),
+        Color::Rgb(0xDB, 0x47, 0x6A),
+        Color::Rgb(0xDC, 0x48, 0x69),
+        Color::Rgb(0xDE, 0x49, 0x68),
+        Color::Rgb(0xDF, 0x4A, 0x68),
+        Color::Rgb(0xE0, 0x4C, 0x67),
+        Color::Rgb(0xE2, 0x4D, 0x66),
+        Color::Rgb(0xE3, 0x4E, 0x65),
+        Color::Rgb(0xE4, 0x4F, 0x64),
+        Color::Rgb(0xE5, 0x50, 0x64),
+        Color::Rgb(0xE7, 0x52, 0x63),
+        Color::Rgb(0xE8, 0x53, 0x62),
+        Color::Rgb(0xE9, 0x54, 0x62),
+        Color::Rgb(0xEA, 0x56, 0x61),
+        Color::Rgb(0xEB, 0x57, 0x60),
+        Color::Rgb(0xEC, 0x58, 0x60),
+        Color::Rgb(0xED, 0x5A, 0x5F),
+        Color::Rgb(0xEE, 0x5B, 0x5E),
+        Color::Rgb(0xEF, 0x5D, 0x5E),
+        Color::Rgb(0xF0, 0x5F, 0x5E),
+        Color::Rgb(0xF1, 0x60, 0x5D),
+        Color::Rgb(0xF2, 0x62, 0x5D),
+        Color::Rgb(0xF2, 0x64, 0x5C),
+        Color::Rgb(0xF3, 0x65, 0x5C),
+        Color::Rgb(0xF4, 0x67, 0x5C),
+        Color::Rgb(0xF4, 0x69, 0x5C),
+        Color::Rgb(0xF5, 0x6B, 0x5C),
+        Color::Rgb(0xF6, 0x6C, 0x5C),
+        Color::Rgb(0xF6, 0x6E, 0x5C),
+        Color::Rgb(0xF7, 0x70, 0x5C),
+        Color::Rgb(0xF7, 0x72, 0x5C),
+        Color::Rgb(0xF8, 0x74, 0x5C),
+        Color::Rgb(0xF8, 0x76, 0x5C),
+        Color::Rgb(0xF9, 0x78, 0x5D),
+        Color::Rgb(0xF9, 0x79, 0x5D),
+        Color::Rgb(0xF9, 0x7B, 0x5D),
+        Color::Rgb(0xFA, 0x7D, 0x5E),
+        Color::Rgb(0xFA, 0x7F, 0x5E),
+        Color::Rgb(0xFA, 0x81, 0x5F),
+        Color::Rgb(0xFB, 0x83, 0x5F),
+        Color::Rgb(0xFB, 0x85, 0x60),
+        Color::Rgb(0xFB, 0x87, 0x61),
+        Color::Rgb(0xFC, 0x89, 0x61),
+        Color::Rgb(0xFC, 0x8A, 0x62),
+        Color::Rgb(0xFC, 0x8C, 0x63),
+        Color::Rgb(0xFC, 0x8E, 0x64),
+        Color::Rgb(0xFC, 0x90, 0x65),
+        Color::Rgb(0xFD, 0x92, 0x66),
+        Color::Rgb(0xFD, 0x94, 0x67),
+        Color::Rgb(0xFD, 0x96, 0x68),
+        Color::Rgb(0xFD, 0x98, 0x69),
+        Color::Rgb(0xFD, 0x9A, 0x6A),
+        Color::Rgb(0xFD, 0x9B, 0x6B),
+        Color::Rgb(0xFE, 0x9D, 0x6C),
+        Color::Rgb(0xFE, 0x9F, 0x6D),
+        Color::Rgb(0xFE, 0xA1, 0x6E),
+        Color::Rgb(0xFE, 0xA3, 0x6F),
+        Color::Rgb(0xFE, 0xA5, 0x71),
+        Color::Rgb(0xFE, 0xA7, 0x72),
+        Color::Rgb(0xFE, 0xA9, 0x73),
+        Color::Rgb(0xFE, 0xAA, 0x74),
+        Color::Rgb(0xFE, 0xAC, 0x76),
+        Color::Rgb(0xFE, 0xAE, 0x77),
+        Color::Rgb(0xFE, 0xB0, 0x78),
+        Color::Rgb(0xFE, 0xB2, 0x7A),
+        Color::Rgb(0xFE, 0xB4, 0x7B),
+        Color::Rgb(0xFE, 0xB6, 0x7C),
+        Color::Rgb(0xFE, 0xB7, 0x7E),
+        Color::Rgb(0xFE, 0xB9, 0x7F),
+        Color::Rgb(0xFE, 0xBB, 0x81),
+        Color::Rgb(0xFE, 0xBD, 0x82),
+        Color::Rgb(0xFE, 0xBF, 0x84),
+        Color::Rgb(0xFE, 0xC1, 0x85),
+        Color::Rgb(0xFE, 0xC2, 0x87),
+        Color::Rgb(0xFE, 0xC4, 0x88),
+        Color::Rgb(0xFE, 0xC6, 0x8A),
+        Color::Rgb(0xFE, 0xC8, 0x8C),
+        Color::Rgb(0xFE, 0xCA, 0x8D),
+        Color::Rgb(0xFE, 0xCC, 0x8F),
+        Color::Rgb(0xFE, 0xCD, 0x90),
+        Color::Rgb(0xFE, 0xCF, 0x92),
+        Color::Rgb(0xFE, 0xD1, 0x94),
+        Color::Rgb(0xFE, 0xD3, 0x95),
+        Color::Rgb(0xFE, 0xD5, 0x97),
+        Color::Rgb(0xFE, 0xD7, 0x99),
+        Color::Rgb(0xFE, 0xD8, 0x9A),
+        Color::Rgb(0xFD, 0xDA, 0x9C),
+        Color::Rgb(0xFD, 0xDC, 0x9E),
+        Color::Rgb(0xFD, 0xDE, 0xA0),
+        Color::Rgb(0xFD, 0xE0, 0xA1),
+        Color::Rgb(0xFD, 0xE2, 0xA3),
+        Color::Rgb(0xFD, 0xE3, 0xA5),
+        Color::Rgb(0xFD, 0xE5, 0xA7),
+        Color::Rgb(0xFD, 0xE7, 0xA9),
+        Color::Rgb(0xFD, 0xE9, 0xAA),
+        Color::Rgb(0xFD, 0xEB, 0xAC),
+        Color::Rgb(0xFC, 0xEC, 0xAE),
+        Color::Rgb(0xFC, 0xEE, 0xB0),
+        Color::Rgb(0xFC, 0xF0, 0xB2),
+        Color::Rgb(0xFC, 0xF2, 0xB4),
+        Color::Rgb(0xFC, 0xF4, 0xB6),
+        Color::Rgb(0xFC, 0xF6, 0xB8),
+        Color::Rgb(0xFC, 0xF7, 0xB9),
+        Color::Rgb(0xFC, 0xF9, 0xBB),
+        Color::Rgb(0xFC, 0xFB, 0xBD),
+        Color::Rgb(0xFC, 0xFD, 0xBF)
+    ];
+}
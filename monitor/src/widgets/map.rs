@@ -0,0 +1,278 @@
+use serde_derive::Deserialize;
+use tui::style::Color;
+use tui::widgets::canvas::Shape;
+
+const MIN_ZOOM: f64 = 1.0;
+const MAX_ZOOM: f64 = 32.0;
+
+/// Fraction of the visible span a single pan step moves the map
+const PAN_STEP: f64 = 0.1;
+
+/// Which satellites the map shows
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MapMode {
+    /// The satellite of the next job of the active station
+    Next,
+    /// The satellites of the upcoming jobs of the active station
+    Station,
+    /// The satellites of the upcoming jobs of all stations
+    All,
+}
+
+impl MapMode {
+    pub fn name(&self) -> &str {
+        match self {
+            MapMode::Next => "next",
+            MapMode::Station => "station",
+            MapMode::All => "all",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            MapMode::Next => MapMode::Station,
+            MapMode::Station => MapMode::All,
+            MapMode::All => MapMode::Next,
+        }
+    }
+}
+
+/// How the globe is drawn onto the map
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MapProjection {
+    /// Longitude and latitude as x and y
+    Equirectangular,
+    /// Distance and direction from the active station, true to scale along the
+    /// great circles through the station
+    Azimuthal,
+}
+
+impl MapProjection {
+    pub fn next(self) -> Self {
+        match self {
+            MapProjection::Equirectangular => MapProjection::Azimuthal,
+            MapProjection::Azimuthal => MapProjection::Equirectangular,
+        }
+    }
+}
+
+/// What the map is centered on
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MapCenter {
+    World,
+    Station,
+    Satellite,
+}
+
+impl MapCenter {
+    pub fn next(self) -> Self {
+        match self {
+            MapCenter::World => MapCenter::Station,
+            MapCenter::Station => MapCenter::Satellite,
+            MapCenter::Satellite => MapCenter::World,
+        }
+    }
+}
+
+/// Visible part of the map in a projection.
+///
+/// Coordinates of the equirectangular projection are degrees of longitude and
+/// latitude, longitudes are wrapped around the center so the map continues
+/// across the antimeridian. The azimuthal projection is centered on `origin`,
+/// its coordinates are degrees of arc from there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapViewport {
+    projection: MapProjection,
+    /// Latitude and longitude of the center of the azimuthal projection
+    origin: (f64, f64),
+    /// Center of the visible part in projected coordinates
+    center: (f64, f64),
+    zoom: f64,
+}
+
+impl MapViewport {
+    /// A viewport showing `focus` (longitude and latitude), the center of the
+    /// projection if there is none, moved by `pan` steps
+    pub fn new(
+        projection: MapProjection,
+        origin: (f64, f64),
+        focus: Option<(f64, f64)>,
+        pan: (i32, i32),
+        zoom: f64,
+    ) -> Self {
+        let mut viewport = MapViewport {
+            projection,
+            origin,
+            center: (0.0, 0.0),
+            zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+        };
+        if let Some((x, y)) = focus.and_then(|(lon, lat)| viewport.project(lon, lat)) {
+            viewport.center = (x, y);
+        }
+
+        let (width, height) = viewport.span();
+        viewport.center.0 += f64::from(pan.0) * width * PAN_STEP;
+        viewport.center.1 += f64::from(pan.1) * height * PAN_STEP;
+        // don't pan off the globe, the equirectangular map wraps around in longitude
+        match projection {
+            MapProjection::Equirectangular => {
+                viewport.center.1 = viewport.center.1.clamp(-90.0, 90.0);
+            }
+            MapProjection::Azimuthal => {
+                viewport.center.0 = viewport.center.0.clamp(-180.0, 180.0);
+                viewport.center.1 = viewport.center.1.clamp(-180.0, 180.0);
+            }
+        }
+
+        viewport
+    }
+
+    /// Pan steps which move the map across the whole globe at a zoom level, more
+    /// steps don't move it any further
+    pub fn max_pan(zoom: f64) -> i32 {
+        (zoom.clamp(MIN_ZOOM, MAX_ZOOM) / PAN_STEP).ceil() as i32
+    }
+
+    pub fn zoom_in(zoom: f64) -> f64 {
+        (zoom * 2.0).min(MAX_ZOOM)
+    }
+
+    pub fn zoom_out(zoom: f64) -> f64 {
+        (zoom / 2.0).max(MIN_ZOOM)
+    }
+
+    /// Width and height of the visible part in projected coordinates
+    fn span(&self) -> (f64, f64) {
+        match self.projection {
+            MapProjection::Equirectangular => (360.0 / self.zoom, 180.0 / self.zoom),
+            MapProjection::Azimuthal => (360.0 / self.zoom, 360.0 / self.zoom),
+        }
+    }
+
+    /// The x and y bounds of a canvas with `aspect` times more braille dots in a
+    /// row than in a column
+    pub fn bounds(&self, aspect: f64) -> ([f64; 2], [f64; 2]) {
+        let (mut width, height) = self.span();
+        // keep the azimuthal projection round, the equirectangular map is
+        // stretched to the canvas like it always was
+        if self.projection == MapProjection::Azimuthal && aspect > 0.0 {
+            width = height * aspect;
+        }
+
+        (
+            [self.center.0 - width / 2.0, self.center.0 + width / 2.0],
+            [self.center.1 - height / 2.0, self.center.1 + height / 2.0],
+        )
+    }
+
+    /// Projects a longitude and latitude in degrees, `None` for the point
+    /// opposite of the origin of the azimuthal projection
+    pub fn project(&self, lon: f64, lat: f64) -> Option<(f64, f64)> {
+        match self.projection {
+            MapProjection::Equirectangular => {
+                Some((self.center.0 + wrap_longitude(lon - self.center.0), lat))
+            }
+            MapProjection::Azimuthal => {
+                let (lat0, lon0) = (self.origin.0.to_radians(), self.origin.1.to_radians());
+                let (lat, delta_lon) = (lat.to_radians(), lon.to_radians() - lon0);
+
+                let cos_c = lat0.sin() * lat.sin() + lat0.cos() * lat.cos() * delta_lon.cos();
+                let distance = cos_c.clamp(-1.0, 1.0).acos().to_degrees();
+                if distance > 179.9 {
+                    return None;
+                }
+                let azimuth = (delta_lon.sin() * lat.cos())
+                    .atan2(lat0.cos() * lat.sin() - lat0.sin() * lat.cos() * delta_lon.cos());
+
+                Some((distance * azimuth.sin(), distance * azimuth.cos()))
+            }
+        }
+    }
+}
+
+/// Wraps a longitude difference into -180° to 180°
+pub fn wrap_longitude(lon: f64) -> f64 {
+    let lon = (lon + 180.0) % 360.0;
+    if lon < 0.0 {
+        lon + 180.0
+    } else {
+        lon - 180.0
+    }
+}
+
+/// A shape given in longitude and latitude drawn in the projection of a viewport
+pub struct Projected<'a, S> {
+    pub shape: &'a S,
+    pub viewport: &'a MapViewport,
+}
+
+impl<'a, S: Shape<'a>> Shape<'a> for Projected<'a, S> {
+    fn color(&self) -> Color {
+        self.shape.color()
+    }
+
+    fn points(&'a self) -> Box<dyn Iterator<Item = (f64, f64)> + 'a> {
+        Box::new(
+            self.shape
+                .points()
+                .filter_map(move |(lon, lat)| self.viewport.project(lon, lat)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-6 && (actual.1 - expected.1).abs() < 1e-6,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn wraps_around_the_antimeridian() {
+        assert_eq!(wrap_longitude(190.0), -170.0);
+        assert_eq!(wrap_longitude(-190.0), 170.0);
+        assert_eq!(wrap_longitude(45.0), 45.0);
+
+        let viewport = MapViewport::new(
+            MapProjection::Equirectangular,
+            (0.0, 0.0),
+            Some((175.0, 10.0)),
+            (0, 0),
+            4.0,
+        );
+        assert_eq!(viewport.bounds(2.0), ([130.0, 220.0], [-12.5, 32.5]));
+        // a ground track crossing the antimeridian continues to the right
+        assert_close(viewport.project(-178.0, 12.0).unwrap(), (182.0, 12.0));
+
+        // panning stops at the poles
+        let viewport = MapViewport::new(
+            MapProjection::Equirectangular,
+            (0.0, 0.0),
+            None,
+            (0, MapViewport::max_pan(1.0) * 2),
+            1.0,
+        );
+        assert_eq!(viewport.bounds(2.0).1, [0.0, 180.0]);
+    }
+
+    #[test]
+    fn projects_azimuthal_equidistant() {
+        let viewport = MapViewport::new(MapProjection::Azimuthal, (48.0, 11.0), None, (0, 0), 1.0);
+        assert_close(viewport.project(11.0, 48.0).unwrap(), (0.0, 0.0));
+        // due north along the meridian and due east along the equator
+        assert_close(viewport.project(11.0, 58.0).unwrap(), (0.0, 10.0));
+        let equator = MapViewport::new(MapProjection::Azimuthal, (0.0, 0.0), None, (0, 0), 1.0);
+        assert_close(equator.project(30.0, 0.0).unwrap(), (30.0, 0.0));
+        assert!(equator.project(180.0, 0.0).is_none());
+        assert_eq!(equator.bounds(1.5), ([-270.0, 270.0], [-180.0, 180.0]));
+    }
+}
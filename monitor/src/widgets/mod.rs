@@ -1,7 +1,23 @@
+mod colormap;
+mod inferno;
 mod info_bar;
+mod magma;
+mod map;
+mod plasma;
+mod ticks;
+mod viewport;
 mod viridis;
 mod waterfall;
 
+pub use self::colormap::ColorMode;
+pub use self::colormap::Colormap;
 pub use self::info_bar::InfoBar;
+pub use self::map::{wrap_longitude, MapCenter, MapMode, MapProjection, MapViewport, Projected};
+pub use self::ticks::tick_label;
+pub use self::ticks::tick_step;
+pub use self::viewport::BinReduction;
+pub use self::viewport::FrequencyViewport;
+pub use self::viridis::VIRIDIS;
+pub use self::waterfall::color_index;
 pub use self::waterfall::Waterfall;
 pub use self::waterfall::WaterfallLegend;
@@ -0,0 +1,264 @@
+use lazy_static::lazy_static;
+use tui::style::Color;
+
+// the matplotlib plasma colormap
+lazy_static! {
+    pub static ref PLASMA: Vec<Color> = vec![
+        Color::Rgb(0x0D, 0x08, 0x87),
+        Color::Rgb(0x10, 0x07, 0x88),
+        Color::Rgb(0x13, 0x07, 0x89),
+        Color::Rgb(0x16, 0x07, 0x8A),
+        Color::Rgb(0x19, 0x06, 0x8C),
+        Color::Rgb(0x1B, 0x06, 0x8D),
+        Color::Rgb(0x1D, 0x06, 0x8E),
+        Color::Rgb(0x20, 0x06, 0x8F),
+        Color::Rgb(0x22, 0x06, 0x90),
+        Color::Rgb(0x24, 0x06, 0x91),
+        Color::Rgb(0x26, 0x05, 0x91),
+        Color::Rgb(0x28, 0x05, 0x92),
+        Color::Rgb(0x2A, 0x05, 0x93),
+        Color::Rgb(0x2C, 0x05, 0x94),
+        Color::Rgb(0x2E, 0x05, 0x95),
+        Color::Rgb(0x2F, 0x05, 0x96),
+        Color::Rgb(0x31, 0x05, 0x97),
+        Color::Rgb(0x33, 0x05, 0x97),
+        Color::Rgb(0x35, 0x04, 0x98),
+        Color::Rgb(0x37, 0x04, 0x99),
+        Color::Rgb(0x38, 0x04, 0x9A),
+        Color::Rgb(0x3A, 0x04, 0x9A),
+        Color::Rgb(0x3C, 0x04, 0x9B),
+        Color::Rgb(0x3E, 0x04, 0x9C),
+        Color::Rgb(0x3F, 0x04, 0x9C),
+        Color::Rgb(0x41, 0x04, 0x9D),
+        Color::Rgb(0x43, 0x03, 0x9E),
+        Color::Rgb(0x44, 0x03, 0x9E),
+        Color::Rgb(0x46, 0x03, 0x9F),
+        Color::Rgb(0x48, 0x03, 0x9F),
+        Color::Rgb(0x49, 0x03, 0xA0),
+        Color::Rgb(0x4B, 0x03, 0xA1),
+        Color::Rgb(0x4C, 0x02, 0xA1),
+        Color::Rgb(0x4E, 0x02, 0xA2),
+        Color::Rgb(0x50, 0x02, 0xA2),
+        Color::Rgb(0x51, 0x02, 0xA3),
+        Color::Rgb(0x53, 0x02, 0xA3),
+        Color::Rgb(0x55, 0x02, 0xA4),
+        Color::Rgb(0x56, 0x01, 0xA4),
+        Color::Rgb(0x58, 0x01, 0xA4),
+        Color::Rgb(0x59, 0x01, 0xA5),
+        Color::Rgb(0x5B, 0x01, 0xA5),
+        Color::Rgb(0x5C, 0x01, 0xA6),
+        Color::Rgb(0x5E, 0x01, 0xA6),
+        Color::Rgb(0x60, 0x01, 0xA6),
+        Color::Rgb(0x61, 0x00, 0xA7),
+        Color::Rgb(0x63, 0x00, 0xA7),
+        Color::Rgb(0x64, 0x00, 0xA7),
+        Color::Rgb(0x66, 0x00, 0xA7),
+        Color::Rgb(0x67, 0x00, 0xA8),
+        Color::Rgb(0x69, 0x00, 0xA8),
+        Color::Rgb(0x6A, 0x00, 0xA8),
+        Color::Rgb(0x6C, 0x00, 0xA8),
+        Color::Rgb(0x6E, 0x00, 0xA8),
+        Color::Rgb(0x6F, 0x00, 0xA8),
+        Color::Rgb(0x71, 0x00, 0xA8),
+        Color::Rgb(0x72, 0x01, 0xA8),
+        Color::Rgb(0x74, 0x01, 0xA8),
+        Color::Rgb(0x75, 0x01, 0xA8),
+        Color::Rgb(0x77, 0x01, 0xA8),
+        Color::Rgb(0x78, 0x01, 0xA8),
+        Color::Rgb(0x7A, 0x02, 0xA8),
+        Color::Rgb(0x7B, 0x02, 0xA8),
+        Color::Rgb(0x7D, 0x03, 0xA8),
+        Color::Rgb(0x7E, 0x03, 0xA8),
+        Color::Rgb(0x80, 0x04, 0xA8),
+        Color::Rgb(0x81, 0x04, 0xA7),
+        Color::Rgb(0x83, 0x05, 0xA7),
+        Color::Rgb(0x84, 0x05, 0xA7),
+        Color::Rgb(0x86, 0x06, 0xA6),
+        Color::Rgb(0x87, 0x07, 0xA6),
+        Color::Rgb(0x88, 0x08, 0xA6),
+        Color::Rgb(0x8A, 0x09, 0xA5),
+        Color::Rgb(0x8B, 0x0A, 0xA5),
+        Color::Rgb(0x8D, 0x0B, 0xA5),
+        Color::Rgb(0x8E, 0x0C, 0xA4),
+        Color::Rgb(0x8F, 0x0D, 0xA4),
+        Color::Rgb(0x91, 0x0E, 0xA3),
+        Color::Rgb(0x92, 0x0F, 0xA3),
+        Color::Rgb(0x94, 0x10, 0xA2),
+        Color::Rgb(0x95, 0x11, 0xA1),
+        Color::Rgb(0x96, 0x13, 0xA1),
+        Color::Rgb(0x98, 0x14, 0xA0),
+        Color::Rgb(0x99, 0x15, 0x9F),
+        Color::Rgb(0x9A, 0x16, 0x9F),
+        Color::Rgb(0x9C, 0x17, 0x9E),
+        Color::Rgb(0x9D, 0x18, 0x9D),
+        Color::Rgb(0x9E, 0x19, 0x9D),
+        Color::Rgb(0xA0, 0x1A, 0x9C),
+        Color::Rgb(0xA1, 0x1B, 0x9B),
+        Color::Rgb(0xA2, 0x1D, 0x9A),
+        Color::Rgb(0xA3, 0x1E, 0x9A),
+        Color::Rgb(0xA5, 0x1F, 0x99),
+        Color::Rgb(0xA6, 0x20, 0x98),
+        Color::Rgb(0xA7, 0x21, 0x97),
+        Color::Rgb(0xA8, 0x22, 0x96),
+        Color::Rgb(0xAA, 0x23, 0x95),
+        Color::Rgb(0xAB, 0x24, 0x94),
+        Color::Rgb(0xAC, 0x26, 0x94),
+        Color::Rgb(0xAD, 0x27, 0x93),
+        Color::Rgb(0xAE, 0x28, 0x92),
+        Color::Rgb(0xB0, 0x29, 0x91),
+        Color::Rgb(0xB1, 0x2A, 0x90),
+        Color::Rgb(0xB2, 0x2B, 0x8F),
+        Color::Rgb(0xB3, 0x2C, 0x8E),
+        Color::Rgb(0xB4, 0x2E, 0x8D),
+        Color::Rgb(0xB5, 0x2F, 0x8C),
+        Color::Rgb(0xB6, 0x30, 0x8B),
+        Color::Rgb(0xB7, 0x31, 0x8A),
+        Color::Rgb(0xB8, 0x32, 0x89),
+        Color::Rgb(0xBA, 0x33, 0x88),
+        Color::Rgb(0xBB, 0x34, 0x88),
+        Color::Rgb(0xBC, 0x35, 0x87),
+        Color::Rgb(0xBD, 0x37, 0x86),
+        Color::Rgb(0xBE, 0x38, 0x85),
+        Color::Rgb(0xBF, 0x39, 0x84),
+        Color::Rgb(0xC0, 0x3A, 0x83),
+        Color::Rgb(0xC1, 0x3B, 0x82),
+        Color::Rgb(0xC2, 0x3C, 0x81),
+        Color::Rgb(0xC3, 0x3D, 0x80),
+        Color::Rgb(0xC4, 0x3E, 0x7F),
+        Color::Rgb(0xC5, 0x40, 0x7E),
+        Color::Rgb(0xC6, 0x41, 0x7D),
+        Color::Rgb(0xC7, 0x42, 0x7C),
+        Color::Rgb(0xC8, 0x43, 0x7B),
+        Color::Rgb(0xC9, 0x44, 0x7A),
+        Color::Rgb(0xCA, 0x45, 0x7A),
+        Color::Rgb(0xCB, 0x46, 0x79),
+        Color::Rgb(0xCC, 0x47, 0x78),
+        Color::Rgb(0xCC, 0x49, 0x77),
+        Color::Rgb(0xCD, 0x4A, 0x76),
+        Color::Rgb(0xCE, 0x4B, 0x75),
+        Color::Rgb(0xCF, 0x4C, 0x74),
+        Color::Rgb(0xD0, 0x4D, 0x73),
+        Color::Rgb(0xD1, 0x4E, 0x72),
+        Color::Rgb(0xD2, 0x4F, 0x71),
+        Color::Rgb(0xD3, 0x51, 0x71),
+        Color::Rgb(0xD4, 0x52, 0x70),
+        Color::Rgb(0xD5, 0x53, 0x6F),
+        Color::Rgb(0xD5, 0x54, 0x6E),
+        Color::Rgb(0xD6, 0x55, 0x6D),
+        Color::Rgb(0xD7, 0x56, 0x6C),
+        Color::Rgb(0xD8, 0x57, 0x6B),
+        Color::Rgb(0xD9, 0x58, 0x6A),
+        Color::Rgb(0xDA, 0x5A, 0x6A),
+        Color::Rgb(0xDA, 0x5B, 0x69),
+        Color::Rgb(0xDB, 0x5C, 0x68),
+        Color::Rgb(0xDC, 0x5D, 0x67),
+        Color::Rgb(0xDD, 0x5E, 0x66),
+        Color::Rgb(0xDE, 0x5F, 0x65),
+        Color::Rgb(0xDE, 0x61, 0x64),
+        Color::Rgb(0xDF, 0x62, 0x63),
+        Color::Rgb(0xE0, 0x63, 0x63),
+        Color::Rgb(0xE1, 0x64, 0x62),
+        Color::Rgb(0xE2, 0x65, 0x61),
+        Color::Rgb(0xE2, 0x66, 0x60),
+        Color::Rgb(0xE3, 0x68, 0x5F),
+        Color::Rgb(0xE4, 0x69, 0x5E),
+        Color::Rgb(0xE5, 0x6A, 0x5D),
+        Color::Rgb(0xE5, 0x6B, 0x5D),
+        Color::Rgb(0xE6, 0x6C, 0x5C),
+        Color::Rgb(0xE7, 0x6E, 0x5B),
+        Color::Rgb(0xE7, 0x6F, 0x5A),
+        Color::Rgb(0xE8, 0x70, 0x59),
+        Color::Rgb(0xE9, 0x71, 0x58),
+        Color::Rgb(0xE9, 0x72, 0x57),
+        Color::Rgb(0xEA, 0x74, 0x57),
+        Color::Rgb(0xEB, 0x75, 0x56),
+        Color::Rgb(0xEB, 0x76, 0x55),
+        Color::Rgb(0xEC, 0x77, 0x54),
+        Color::Rgb(0xED, 0x79, 0x53),
+        Color::Rgb(0xED, 0x7A, 0x52),
+        Color::Rgb(0xEE, 0x7B, 0x51),
+        Color::Rgb(0xEF, 0x7C, 0x51),
+        Color::Rgb(0xEF, 0x7E, 0x50),
+        Color::Rgb(0xF0, 0x7F, 0x4F),
+        Color::Rgb(0xF0, 0x80, 0x4E),
+        Color::Rgb(0xF1, 0x81, 0x4D),
+        Color::Rgb(0xF1, 0x83, 0x4C),
+        Color::Rgb(0xF2, 0x84, 0x4B),
+        Color::Rgb(0xF3, 0x85, 0x4B),
+        Color::Rgb(0xF3, 0x87, 0x4A),
+        Color::Rgb(0xF4, 0x88, 0x49),
+        Color::Rgb(0xF4, 0x89, 0x48),
+        Color::Rgb(0xF5, 0x8B, 0x47),
+        Color::Rgb(0xF5, 0x8C, 0x46),
+        Color::Rgb(0xF6, 0x8D, 0x45),
+        Color::Rgb(0xF6, 0x8F, 0x44),
+        Color::Rgb(0xF7, 0x90, 0x44),
+        Color::Rgb(0xF7, 0x91, 0x43),
+        Color::Rgb(0xF7, 0x93, 0x42),
+        Color::Rgb(0xF8, 0x94, 0x41),
+        Color::Rgb(0xF8, 0x95, 0x40),
+        Color::Rgb(0xF9, 0x97, 0x3F),
+        Color::Rgb(0xF9, 0x98, 0x3E),
+        Color::Rgb(0xF9, 0x9A, 0x3E),
+        Color::Rgb(0xFA, 0x9B, 0x3D),
+        Color::Rgb(0xFA, 0x9C, 0x3C),
+        Color::Rgb(0xFA, 0x9E, 0x3B),
+        Color::Rgb(0xFB, 0x9F, 0x3A),
+        Color::Rgb(0xFB, 0xA1, 0x39),
+        Color::Rgb(0xFB, 0xA2, 0x38),
+        Color::Rgb(0xFC, 0xA3, 0x38),
+        Color::Rgb(0xFC, 0xA5, 0x37),
+        Color::Rgb(0xFC, 0xA6, 0x36),
+        Color::Rgb(0xFC, 0xA8, 0x35),
+        Color::Rgb(0xFC, 0xA9, 0x34),
+        Color::Rgb(0xFD, 0xAB, 0x33),
+        Color::Rgb(0xFD, 0xAC, 0x33),
+        Color::Rgb(0xFD, 0xAE, 0x32),
+        Color::Rgb(0xFD, 0xAF, 0x31),
+        Color::Rgb(0xFD, 0xB1, 0x30),
+        Color::Rgb(0xFD, 0xB2, 0x2F),
+        Color::Rgb(0xFD, 0xB4, 0x2F),
+        Color::Rgb(0xFD, 0xB5, 0x2E),
+        Color::Rgb(0xFE, 0xB7, 0x2D),
+        Color::Rgb(0xFE, 0xB8, 0x2C),
+        Color::Rgb(0xFE, 0xBA, 0x2C),
+        Color::Rgb(0xFE, 0xBB, 0x2B),
+        Color::Rgb(0xFE, 0xBD, 0x2A),
+        Color::Rgb(0xFE, 0xBE, 0x2A),
+        Color::Rgb(0xFE, 0xC0, 0x29),
+        Color::Rgb(0xFD, 0xC2, 0x29),
+        Color::Rgb(0xFD, 0xC3, 0x28),
+        Color::Rgb(0xFD, 0xC5, 0x27),
+        Color::Rgb(0xFD, 0xC6, 0x27),
+        Color::Rgb(0xFD, 0xC8, 0x27),
+        Color::Rgb(0xFD, 0xCA, 0x26),
+        Color::Rgb(0xFD, 0xCB, 0x26),
+        Color::Rgb(0xFC, 0xCD, 0x25),
+        Color::Rgb(0xFC, 0xCE, 0x25),
+        Color::Rgb(0xFC, 0xD0, 0x25),
+        Color::Rgb(0xFC, 0xD2, 0x25),
+        Color::Rgb(0xFB, 0xD3, 0x24),
+        Color::Rgb(0xFB, 0xD5, 0x24),
+        Color::Rgb(0xFB, 0xD7, 0x24),
+        Color::Rgb(0xFA, 0xD8, 0x24),
+        Color::Rgb(0xFA, 0xDA, 0x24),
+        Color::Rgb(0xF9, 0xDC, 0x24),
+        Color::Rgb(0xF9, 0xDD, 0x25),
+        Color::Rgb(0xF8, 0xDF, 0x25),
+        Color::Rgb(0xF8, 0xE1, 0x25),
+        Color::Rgb(0xF7, 0xE2, 0x25),
+        Color::Rgb(0xF7, 0xE4, 0x25),
+        Color::Rgb(0xF6, 0xE6, 0x26),
+        Color::Rgb(0xF6, 0xE8, 0x26),
+        Color::Rgb(0xF5, 0xE9, 0x26),
+        Color::Rgb(0xF5, 0xEB, 0x27),
+        Color::Rgb(0xF4, 0xED, 0x27),
+        Color::Rgb(0xF3, 0xEE, 0x27),
+        Color::Rgb(0xF3, 0xF0, 0x27),
+        Color::Rgb(0xF2, 0xF2, 0x27),
+        Color::Rgb(0xF1, 0xF4, 0x26),
+        Color::Rgb(0xF1, 0xF5, 0x25),
+        Color::Rgb(0xF0, 0xF7, 0x24),
+        Color::Rgb(0xF0, 0xF9, 0x21)
+    ];
+}
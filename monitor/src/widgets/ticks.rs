@@ -0,0 +1,32 @@
+/// Picks a step of 1, 2 or 5 times a power of ten which divides `range` into at
+/// most `ticks` parts
+pub fn tick_step(range: f64, ticks: f64) -> f64 {
+    let raw = range / ticks;
+    let magnitude = 10f64.powf(raw.log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Formats a tick with as many decimals as the step needs
+pub fn tick_label(tick: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    // adding 0.0 turns -0.0 into 0.0
+    format!("{:.*}", decimals, tick + 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_readable_ticks() {
+        assert_eq!(tick_step(48.0, 8.0), 10.0);
+        assert_eq!(tick_step(600.0, 10.0), 100.0);
+        assert_eq!(tick_label(-20.0, 10.0), "-20");
+        assert_eq!(tick_label(-0.0, 0.5), "0.0");
+    }
+}
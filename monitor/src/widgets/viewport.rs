@@ -0,0 +1,197 @@
+use serde_derive::Deserialize;
+
+const MIN_ZOOM: f32 = 1.0;
+const MAX_ZOOM: f32 = 10.0;
+const ZOOM_STEP: f32 = 0.5;
+
+/// Fraction of the visible span a single pan step moves the viewport
+const PAN_STEP: f32 = 0.1;
+
+/// How the FFT bins which fall into a single column are combined
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BinReduction {
+    Max,
+    Mean,
+    Min,
+}
+
+impl BinReduction {
+    pub fn name(&self) -> &str {
+        match self {
+            BinReduction::Max => "max",
+            BinReduction::Mean => "mean",
+            BinReduction::Min => "min",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            BinReduction::Max => BinReduction::Mean,
+            BinReduction::Mean => BinReduction::Min,
+            BinReduction::Min => BinReduction::Max,
+        }
+    }
+
+    fn reduce(&self, bins: &[f32]) -> f32 {
+        match self {
+            BinReduction::Max => bins.iter().fold(f32::MIN, |res, val| res.max(*val)),
+            BinReduction::Mean => bins.iter().sum::<f32>() / bins.len() as f32,
+            BinReduction::Min => bins.iter().fold(f32::MAX, |res, val| res.min(*val)),
+        }
+    }
+}
+
+/// Visible part of the spectrum, shared by the spectrum plot and the waterfall.
+///
+/// `center` is an offset from the center frequency of the recording in Hz, the
+/// visible span is the full bandwidth divided by `zoom`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrequencyViewport {
+    center: f32,
+    zoom: f32,
+}
+
+impl FrequencyViewport {
+    pub fn new(zoom: f32) -> Self {
+        FrequencyViewport {
+            center: 0.0,
+            zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+        }
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom + ZOOM_STEP).min(MAX_ZOOM);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom - ZOOM_STEP).max(MIN_ZOOM);
+    }
+
+    /// Moves the viewport by `steps` tenths of the visible span, negative steps pan
+    /// to lower frequencies
+    pub fn pan(&mut self, frequencies: &[f32], steps: i32) {
+        let bounds = self.bounds(frequencies);
+        let span = bounds[1] - bounds[0];
+        self.center = (bounds[0] + bounds[1]) / 2.0 + steps as f32 * PAN_STEP * span;
+        // store the clamped center so panning back starts at the visible edge
+        let bounds = self.bounds(frequencies);
+        self.center = (bounds[0] + bounds[1]) / 2.0;
+    }
+
+    /// Centers the viewport on the center frequency again
+    pub fn reset(&mut self) {
+        self.center = 0.0;
+    }
+
+    /// Lowest and highest visible frequency, the viewport never leaves the range
+    /// covered by the bins centered at `frequencies`
+    pub fn bounds(&self, frequencies: &[f32]) -> [f32; 2] {
+        let (first, last) = match (frequencies.first(), frequencies.last()) {
+            (Some(first), Some(last)) if frequencies.len() > 1 => (*first, *last),
+            _ => return [0.0, 0.0],
+        };
+
+        let half_bin = (last - first) / (frequencies.len() - 1) as f32 / 2.0;
+        let (first, last) = (first - half_bin, last + half_bin);
+
+        let span = (last - first) / self.zoom;
+        let low = (self.center - span / 2.0).max(first).min(last - span);
+
+        [low, low + span]
+    }
+
+    /// Reduces `row` to `width` columns covering the visible frequencies.
+    ///
+    /// Every column gets the bins whose center frequency falls into the column, if
+    /// the viewport is zoomed in further than the FFT resolution the nearest bin is
+    /// repeated.
+    pub fn resample(
+        &self,
+        frequencies: &[f32],
+        row: &[f32],
+        width: usize,
+        reduction: BinReduction,
+    ) -> Vec<f32> {
+        let bins = row.len().min(frequencies.len());
+        if bins == 0 || width == 0 {
+            return vec![];
+        }
+        if bins == 1 {
+            return vec![row[0]; width];
+        }
+
+        let first = frequencies[0];
+        let step = (frequencies[bins - 1] - first) / (bins - 1) as f32;
+        let bounds = self.bounds(&frequencies[..bins]);
+        let column_span = (bounds[1] - bounds[0]) / width as f32;
+
+        // number of bins centered below `frequency`
+        let bin_index = |frequency: f32| {
+            ((frequency - first) / step + 0.5)
+                .floor()
+                .max(0.0)
+                .min(bins as f32) as usize
+        };
+
+        (0..width)
+            .map(|column| {
+                let low = bounds[0] + column as f32 * column_span;
+                let start = bin_index(low).min(bins - 1);
+                let end = bin_index(low + column_span).max(start + 1);
+
+                reduction.reduce(&row[start..end])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frequencies(bins: usize) -> Vec<f32> {
+        (0..bins)
+            .map(|bin| bin as f32 + 0.5 - (bins / 2) as f32)
+            .collect()
+    }
+
+    #[test]
+    fn viewport_stays_inside_spectrum() {
+        let frequencies = frequencies(100);
+        let mut viewport = FrequencyViewport::new(2.0);
+        assert_eq!(viewport.bounds(&frequencies), [-25.0, 25.0]);
+
+        viewport.pan(&frequencies, 100);
+        assert_eq!(viewport.bounds(&frequencies), [0.0, 50.0]);
+
+        viewport.pan(&frequencies, -1);
+        assert_eq!(viewport.bounds(&frequencies), [-5.0, 45.0]);
+
+        viewport.zoom_out();
+        viewport.zoom_out();
+        assert_eq!(viewport.bounds(&frequencies), [-50.0, 50.0]);
+    }
+
+    #[test]
+    fn resamples_uneven_widths() {
+        let frequencies = frequencies(10);
+        let row = (0..10).map(|bin| bin as f32).collect::<Vec<_>>();
+        let viewport = FrequencyViewport::new(1.0);
+
+        let columns = viewport.resample(&frequencies, &row, 4, BinReduction::Max);
+        assert_eq!(columns, vec![1.0, 4.0, 6.0, 9.0]);
+
+        let columns = viewport.resample(&frequencies, &row, 4, BinReduction::Min);
+        assert_eq!(columns, vec![0.0, 2.0, 5.0, 7.0]);
+
+        let columns = viewport.resample(&frequencies, &row, 20, BinReduction::Mean);
+        assert_eq!(columns.len(), 20);
+        assert_eq!(columns[0], 0.0);
+        assert_eq!(columns[19], 9.0);
+    }
+}
@@ -1,4 +1,6 @@
-use super::viridis::VIRIDIS;
+use super::colormap::{ColorMode, Colormap};
+use super::ticks::{tick_label, tick_step};
+use super::viewport::{BinReduction, FrequencyViewport};
 
 use tui::{
     buffer::Buffer,
@@ -7,10 +9,23 @@ use tui::{
     widgets::{Block, Widget},
 };
 
+/// Columns of the time labels right of the waterfall
+const TIME_AXIS_WIDTH: u16 = 6;
+/// Lines of the frequency ruler below the waterfall
+const FREQUENCY_AXIS_HEIGHT: u16 = 2;
+
+/// Maps a power value to the index of its color in a 256 entry colormap
+pub fn color_index(db: f32, bounds: [f32; 2]) -> usize {
+    let db = db.max(bounds[0]).min(bounds[1]);
+    (255.0 * (db - bounds[0]) / (bounds[1] - bounds[0])).floor() as usize
+}
+
 #[derive(Default)]
 pub struct WaterfallLayout {
     legend_area: Option<Rect>,
     data_area: Rect,
+    frequency_area: Option<Rect>,
+    time_area: Option<Rect>,
 }
 
 pub struct WaterfallLegend<'a, L>
@@ -52,10 +67,18 @@ pub struct Waterfall<'a, L>
 where
     L: AsRef<str> + 'a,
 {
+    axis_style: Style,
     block: Option<Block<'a>>,
     bounds: [f32; 2],
+    color_mode: ColorMode,
+    colormap: Colormap,
     data: &'a [(i64, Vec<f32>)],
+    frequencies: &'a [f32],
     legend: Option<WaterfallLegend<'a, L>>,
+    marker: Option<f32>,
+    marker_style: Style,
+    reduction: BinReduction,
+    viewport: FrequencyViewport,
 }
 
 impl<'a, L> Default for Waterfall<'a, L>
@@ -64,10 +87,18 @@ where
 {
     fn default() -> Self {
         Waterfall {
+            axis_style: Default::default(),
             block: None,
             bounds: [-100.0, 0.0],
+            color_mode: ColorMode::TrueColor,
+            colormap: Colormap::Viridis,
             data: Default::default(),
+            frequencies: Default::default(),
             legend: None,
+            marker: None,
+            marker_style: Default::default(),
+            reduction: BinReduction::Max,
+            viewport: FrequencyViewport::new(1.0),
         }
     }
 }
@@ -76,6 +107,11 @@ impl<'a, L> Waterfall<'a, L>
 where
     L: AsRef<str>,
 {
+    pub fn axis_style(mut self, style: Style) -> Self {
+        self.axis_style = style;
+        self
+    }
+
     pub fn block(mut self, block: Block<'a>) -> Self {
         self.block = Some(block);
         self
@@ -86,16 +122,49 @@ where
         self
     }
 
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.color_mode = mode;
+        self
+    }
+
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
     pub fn data(mut self, data: &'a [(i64, Vec<f32>)]) -> Self {
         self.data = data;
         self
     }
 
+    pub fn frequencies(mut self, frequencies: &'a [f32]) -> Self {
+        self.frequencies = frequencies;
+        self
+    }
+
     pub fn legend(mut self, legend: WaterfallLegend<'a, L>) -> Self {
         self.legend = Some(legend);
         self
     }
 
+    /// Marks a frequency offset on the frequency ruler, e.g. where the signal is
+    /// expected
+    pub fn marker(mut self, frequency: Option<f32>, style: Style) -> Self {
+        self.marker = frequency;
+        self.marker_style = style;
+        self
+    }
+
+    pub fn reduction(mut self, reduction: BinReduction) -> Self {
+        self.reduction = reduction;
+        self
+    }
+
+    pub fn viewport(mut self, viewport: FrequencyViewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
     fn layout(&self, area: Rect) -> WaterfallLayout {
         let mut layout = WaterfallLayout::default();
         if area.height == 0 || area.width == 0 {
@@ -103,19 +172,117 @@ where
         }
 
         let mut x = area.left();
-        let y = area.bottom() - 1;
+        let mut right = area.right();
+        let mut bottom = area.bottom();
 
-        if self.legend.is_some() && y > area.top() {
-            layout.legend_area = Some(Rect::new(x, area.top(), x + 7, y - area.top() + 1));
+        if self.frequencies.len() > 1 && area.height > FREQUENCY_AXIS_HEIGHT + 2 {
+            bottom -= FREQUENCY_AXIS_HEIGHT;
+        }
+        let height = bottom - area.top();
+
+        if self.legend.is_some() {
+            layout.legend_area = Some(Rect::new(x, area.top(), 7, height));
             x += 7;
         }
 
-        if x < area.right() && y > 1 {
-            layout.data_area = Rect::new(x, area.top(), area.right() - x, y - area.top() + 1);
+        if right.saturating_sub(x) > TIME_AXIS_WIDTH + 10 {
+            right -= TIME_AXIS_WIDTH;
+            layout.time_area = Some(Rect::new(right, area.top(), TIME_AXIS_WIDTH, height));
+        }
+
+        if x < right {
+            layout.data_area = Rect::new(x, area.top(), right - x, height);
+
+            if bottom < area.bottom() {
+                layout.frequency_area =
+                    Some(Rect::new(x, bottom, right - x, FREQUENCY_AXIS_HEIGHT));
+            }
         }
 
         layout
     }
+
+    /// Draws a ruler with the frequency offsets in kHz and the marker
+    fn draw_frequency_axis(&self, area: Rect, buf: &mut Buffer) {
+        let bounds = self.viewport.bounds(self.frequencies);
+        let span = bounds[1] - bounds[0];
+        if span <= 0.0 {
+            return;
+        }
+
+        let column = |frequency: f32| {
+            ((frequency - bounds[0]) / span * area.width as f32)
+                .floor()
+                .max(0.0)
+                .min((area.width - 1) as f32) as u16
+        };
+
+        for x in 0..area.width {
+            buf.set_string(area.left() + x, area.top(), "─", self.axis_style);
+        }
+
+        let khz = [bounds[0] as f64 / 1000.0, bounds[1] as f64 / 1000.0];
+        let step = tick_step(khz[1] - khz[0], (area.width / 10).max(1) as f64);
+        let mut free_x = area.left();
+        for tick in ((khz[0] / step).ceil() as i64..=(khz[1] / step).floor() as i64)
+            .map(|index| index as f64 * step)
+        {
+            let x = area.left() + column((tick * 1000.0) as f32);
+            buf.set_string(x, area.top(), "┬", self.axis_style);
+
+            let label = tick_label(tick, step);
+            let label_x = x.saturating_sub(label.len() as u16 / 2).max(free_x);
+            if label_x + label.len() as u16 <= area.right() {
+                buf.set_string(label_x, area.top() + 1, &label, self.axis_style);
+                free_x = label_x + label.len() as u16 + 1;
+            }
+        }
+
+        if let Some(marker) = self.marker {
+            if marker >= bounds[0] && marker <= bounds[1] {
+                buf.set_string(
+                    area.left() + column(marker),
+                    area.top(),
+                    "▲",
+                    self.marker_style,
+                );
+            }
+        }
+    }
+
+    /// Labels the lines of the waterfall with the time since the start of the
+    /// observation whenever a tick is crossed
+    fn draw_time_axis(&self, area: Rect, buf: &mut Buffer) {
+        let seconds = self
+            .data
+            .iter()
+            .rev()
+            .step_by(2)
+            .take(area.height as usize)
+            .map(|(timestamp, _)| *timestamp as f64 / 1_000_000.0)
+            .collect::<Vec<_>>();
+
+        let duration = match (seconds.first(), seconds.last()) {
+            (Some(newest), Some(oldest)) if newest > oldest => newest - oldest,
+            _ => return,
+        };
+        let step = tick_step(duration, (area.height / 3).max(1) as f64).max(1.0);
+
+        for (line, pair) in seconds.windows(2).enumerate() {
+            let tick = (pair[0] / step).floor();
+            if tick <= (pair[1] / step).floor() {
+                continue;
+            }
+
+            let tick = (tick * step) as i64;
+            buf.set_string(
+                area.left(),
+                area.top() + line as u16,
+                format!("─{:>2}:{:02}", tick / 60, tick % 60),
+                self.axis_style,
+            );
+        }
+    }
 }
 
 impl<'a, L> Widget for Waterfall<'a, L>
@@ -136,6 +303,7 @@ where
         }
 
         let layout = self.layout(area);
+        let colors = self.colormap.palette(self.color_mode);
 
         if let Some(area) = layout.legend_area {
             let color_step = 255.0 / (area.height as f32 * 2.0);
@@ -152,8 +320,8 @@ where
                     area.top() + line,
                     "▀▀",
                     Style::default()
-                        .fg(VIRIDIS[top_index])
-                        .bg(VIRIDIS[bottom_index]),
+                        .fg(colors[top_index])
+                        .bg(colors[bottom_index]),
                 );
             }
 
@@ -174,20 +342,34 @@ where
             }
         }
 
+        if let Some(area) = layout.frequency_area {
+            self.draw_frequency_axis(area, buf);
+
+            if let Some(time_area) = layout.time_area {
+                buf.set_string(time_area.left() + 1, area.top() + 1, "kHz", self.axis_style);
+            }
+        }
+
         if self.data.is_empty() {
             return;
         }
 
+        if let Some(area) = layout.time_area {
+            self.draw_time_axis(area, buf);
+        }
+
         let area = layout.data_area;
-        let bin_size = self.data[0].1.len() / (area.width as usize);
+        let width = area.width as usize;
+        let columns = |row: &[f32]| {
+            self.viewport
+                .resample(self.frequencies, row, width, self.reduction)
+        };
 
         const PIX: &str = "▀";
 
         let lines = area.height as usize * 2;
         let rows = self.data.iter().rev().take(lines);
 
-        let db_range = self.bounds[1] - self.bounds[0];
-
         for (row, chunk) in rows
             .collect::<Vec<&(i64, Vec<f32>)>>()
             .chunks(2)
@@ -195,59 +377,26 @@ where
         {
             let mut chunk = chunk.iter();
             if let Some((_timestamp, row_data)) = chunk.next() {
-                let columns = row_data
-                    .chunks(bin_size)
-                    .map(|chunk| chunk.iter().fold(self.bounds[0], |res, val| res.max(*val)))
-                    .collect::<Vec<f32>>();
+                let first = columns(row_data);
 
                 let styles = if let Some((_timestamp, row_data)) = chunk.next() {
-                    columns
+                    first
                         .iter()
-                        .zip(
-                            row_data
-                                .chunks(bin_size)
-                                .map(|chunk| {
-                                    chunk.iter().fold(self.bounds[0], |res, val| res.max(*val))
-                                })
-                                .collect::<Vec<f32>>(),
-                        )
+                        .zip(columns(row_data))
                         .map(|(first, second)| {
                             Style::default()
-                                .fg(VIRIDIS[255
-                                    - ((255.0 / db_range * (first - self.bounds[1])).abs().floor()
-                                        as usize)
-                                        .min(255)])
-                                .bg(
-                                    VIRIDIS[255
-                                        - ((255.0 / db_range * (second - self.bounds[1]))
-                                            .abs()
-                                            .floor()
-                                            as usize)
-                                            .min(255)],
-                                )
+                                .fg(colors[color_index(*first, self.bounds)])
+                                .bg(colors[color_index(second, self.bounds)])
                         })
                         .collect::<Vec<_>>()
                 } else {
-                    columns
+                    first
                         .iter()
-                        .map(|db| {
-                            Style::default().fg(VIRIDIS[255
-                                - ((255.0 / db_range * (db - self.bounds[1])).abs().floor()
-                                    as usize)
-                                    .min(255)])
-                        })
+                        .map(|db| Style::default().fg(colors[color_index(*db, self.bounds)]))
                         .collect::<Vec<_>>()
                 };
 
-                // we do not interpolate between pixels so we just zoom slightly in and display
-                // the area.width pixel in the center of the waterfall
-                let start = (styles.len() - area.width as usize) / 2;
-                for (column, style) in styles
-                    .iter()
-                    .skip(start)
-                    .take(area.width as usize)
-                    .enumerate()
-                {
+                for (column, style) in styles.iter().enumerate() {
                     buf.set_string(
                         area.left() + column as u16,
                         area.top() + row as u16,